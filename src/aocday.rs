@@ -1,8 +1,12 @@
 use std::fmt::{Debug, Display, Formatter};
 use std::io::Lines;
 use std::ops::Deref;
+use std::str::FromStr;
 use anyhow::{anyhow, Error};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use crate::main;
+use crate::scan::{FromFields, Pattern};
 
 pub trait AocDay {
 
@@ -14,6 +18,11 @@ pub trait AocDay {
 
     fn part2(&self) -> Result<AocOutput, Error>;
 
+    // Lets the calendar tell stubs from solutions without running anything.
+    fn implemented_parts(&self) -> u8 {
+        2
+    }
+
 }
 
 pub struct AocInfo {
@@ -58,67 +67,309 @@ impl From<(u8, &str)> for AocInfo {
     }
 }
 
+#[derive(Clone, Debug, Default)]
 pub struct AocInput {
-    lines: Vec<String>,
     raw: String,
-    pos: usize,
+    anomalies: Vec<Anomaly>,
 }
 
 impl AocInput {
     pub fn new(raw: &str) -> Self {
-        Self {
-            lines: raw.lines().map(str::to_string).collect(),
-            raw: raw.to_string(),
-            pos: 0,
-        }
+        raw.to_string().into()
+    }
+
+    pub fn dedent(text: &str) -> Self {
+        dedent(text).into()
+    }
+
+    fn derived(raw: String) -> Self {
+        Self { raw, anomalies: Vec::new() }
+    }
+
+    pub fn anomalies(&self) -> &[Anomaly] {
+        &self.anomalies
     }
 
-    pub fn lines(&self) -> Vec<String> {
-        self.lines.clone()
+    pub fn lines(&self) -> std::str::Lines<'_> {
+        self.raw.lines()
     }
 
-    pub fn raw(&self) -> String {
-        self.raw.clone()
+    pub fn raw(&self) -> &str {
+        self.raw.as_str()
     }
 
     pub fn len(&self) -> usize {
         self.raw.len()
     }
 
-    pub fn trim(&self) -> Self {
-        let trimmed_lines: Vec<String> = self.lines.iter()
-            .map(|s| s.trim().to_string())
-            .collect();
+    pub fn sections(&self) -> Vec<AocInput> {
+        let mut sections = Vec::new();
+        let mut current = String::new();
+        for line in self.lines() {
+            if line.trim().is_empty() {
+                if !current.is_empty() {
+                    sections.push(Self::derived(std::mem::take(&mut current)));
+                }
+            } else {
+                current.push_str(line);
+                current.push('\n');
+            }
+        }
+        if !current.is_empty() {
+            sections.push(Self::derived(current));
+        }
+        sections
+    }
+
+    pub fn sections_exact<const N: usize>(&self) -> Result<[AocInput; N], Error> {
+        let sections = self.sections();
+        let count = sections.len();
+        sections.try_into()
+            .map_err(|_| anyhow!("expected {N} blank-line separated sections, found {count}"))
+    }
+
+    pub fn ints<T>(&self) -> Result<Vec<T>, Error> where T: FromStr, T::Err: Display {
+        Ok(self.line_ints()?.into_iter().flatten().collect())
+    }
+
+    pub fn line_ints<T>(&self) -> Result<Vec<Vec<T>>, Error> where T: FromStr, T::Err: Display {
+        self.lines()
+            .enumerate()
+            .map(|(line_num, line)| {
+                find_ints(line).into_iter()
+                    .map(|(col, token)| parse_at(line, line_num, col, token))
+                    .collect()
+            })
+            .collect()
+    }
+
+    pub fn parse_lines<T>(&self) -> Result<Vec<T>, Error> where T: FromStr, T::Err: Display {
+        self.lines()
+            .enumerate()
+            .map(|(line_num, line)| parse_at(line, line_num, 0, line))
+            .collect()
+    }
+
+    pub fn split_once_each(&self, sep: &str) -> Result<Vec<(&str, &str)>, Error> {
+        self.lines()
+            .enumerate()
+            .map(|(line_num, line)| {
+                line.split_once(sep)
+                    .ok_or_else(|| anyhow!("line {}: expected separator {sep:?} in {line:?}", line_num + 1))
+            })
+            .collect()
+    }
+
+    pub fn scan_lines<T: FromFields>(&self, pattern: &str) -> Result<Vec<T>, Error> {
+        let pattern = Pattern::new(pattern)?;
+        pattern.check_arity::<T>()?;
+        self.lines()
+            .enumerate()
+            .map(|(line_num, line)| pattern.scan(line).map_err(|e| anyhow!("line {}, {e}", line_num + 1)))
+            .collect()
+    }
 
-        Self {
-            lines: trimmed_lines.clone(),
-            raw: trimmed_lines.join("\n"),
-            pos: 0,
+    pub fn columns<T>(&self) -> Result<Vec<Vec<T>>, Error> where T: FromStr, T::Err: Display {
+        let mut columns: Vec<Vec<T>> = Vec::new();
+        for (line_num, line) in self.lines().enumerate() {
+            let fields = line.split_whitespace()
+                .map(|field| (field.as_ptr() as usize - line.as_ptr() as usize, field))
+                .collect::<Vec<_>>();
+
+            if line_num == 0 {
+                columns.resize_with(fields.len(), Vec::new);
+            } else if fields.len() != columns.len() {
+                return Err(anyhow!("line {}: expected {} columns, found {}", line_num + 1, columns.len(), fields.len()));
+            }
+
+            for (column, (col, field)) in columns.iter_mut().zip(fields) {
+                column.push(parse_at(line, line_num, col, field)?);
+            }
         }
+        Ok(columns)
     }
 }
 
-impl Clone for AocInput {
-    fn clone(&self) -> Self {
-        Self {
-            lines: self.lines.clone(),
-            raw: self.raw.clone(),
-            pos: 0
+fn find_ints(line: &str) -> Vec<(usize, &str)> {
+    let bytes = line.as_bytes();
+    let mut ints = Vec::new();
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let negative = bytes[idx] == b'-'
+            && bytes.get(idx + 1).is_some_and(u8::is_ascii_digit)
+            && (idx == 0 || !bytes[idx - 1].is_ascii_digit());
+        if negative || bytes[idx].is_ascii_digit() {
+            let start = idx;
+            idx += 1;
+            while idx < bytes.len() && bytes[idx].is_ascii_digit() {
+                idx += 1;
+            }
+            ints.push((start, &line[start..idx]));
+        } else {
+            idx += 1;
         }
     }
+    ints
+}
+
+fn parse_at<T>(line: &str, line_num: usize, col: usize, token: &str) -> Result<T, Error> where T: FromStr, T::Err: Display {
+    token.parse().map_err(|e| {
+        anyhow!("line {}, column {}: cannot parse {token:?}: {e}", line_num + 1, line[..col].chars().count() + 1)
+    })
 }
 
-impl Iterator for AocInput {
-    type Item = String;
+impl From<String> for AocInput {
+    fn from(raw: String) -> Self {
+        let (raw, anomalies) = normalize(raw);
+        Self { raw, anomalies }
+    }
+}
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let next = self.lines.get(self.pos);
-        self.pos += 1;
-        next.map(String::clone)
+impl From<&str> for AocInput {
+    fn from(raw: &str) -> Self {
+        Self::new(raw)
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Anomaly {
+    ByteOrderMark,
+    CarriageReturns,
+    MissingFinalNewline,
+    TrailingBlankLines(usize),
+    TrailingWhitespace { lines: usize, first: usize },
+    NonAscii { line: usize, col: usize },
+    RaggedWidths { width: usize, min: usize, max: usize, first: usize },
+}
+
+impl Display for Anomaly {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Anomaly::ByteOrderMark => write!(f, "UTF-8 byte order mark removed"),
+            Anomaly::CarriageReturns => write!(f, "CRLF line endings converted to LF"),
+            Anomaly::MissingFinalNewline => write!(f, "missing final newline added"),
+            Anomaly::TrailingBlankLines(count) => write!(f, "{count} trailing blank line(s)"),
+            Anomaly::TrailingWhitespace { lines, first } =>
+                write!(f, "trailing whitespace on {lines} line(s), first on line {first}"),
+            Anomaly::NonAscii { line, col } => write!(f, "non-ASCII character at line {line}, column {col}"),
+            Anomaly::RaggedWidths { width, min, max, first } =>
+                write!(f, "ragged lines: most are {width} wide but widths range {min}..={max}, first on line {first}"),
+        }
+    }
+}
+
+pub fn dedent(text: &str) -> String {
+    let lines: Vec<&str> = text.split('\n').collect();
+    let indent = lines.iter()
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start_matches(' ').len())
+        .min()
+        .unwrap_or(0);
+
+    let start = if lines.first().is_some_and(|line| line.trim().is_empty()) { 1 } else { 0 };
+    let end = if lines.len() > start && lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.len() - 1
+    } else {
+        lines.len()
+    };
+
+    let mut out = String::with_capacity(text.len());
+    for (idx, line) in lines.iter().enumerate().take(end).skip(start) {
+        if idx > 0 {
+            out.push_str(&line[indent.min(line.len() - line.trim_start_matches(' ').len())..]);
+        } else {
+            out.push_str(line);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn normalize(mut raw: String) -> (String, Vec<Anomaly>) {
+    let mut anomalies = Vec::new();
+
+    if let Some(stripped) = raw.strip_prefix('\u{feff}') {
+        raw = stripped.to_string();
+        anomalies.push(Anomaly::ByteOrderMark);
+    }
+    if raw.contains('\r') {
+        raw = raw.replace("\r\n", "\n").replace('\r', "\n");
+        anomalies.push(Anomaly::CarriageReturns);
+    }
+    if !raw.is_empty() && !raw.ends_with('\n') {
+        raw.push('\n');
+        anomalies.push(Anomaly::MissingFinalNewline);
+    }
+
+    anomalies.extend(detect_anomalies(raw.as_str()));
+    (raw, anomalies)
+}
+
+fn detect_anomalies(raw: &str) -> Vec<Anomaly> {
+    let mut anomalies = Vec::new();
+    // Nearly every input is ASCII, where widths are byte lengths and the
+    // non-ASCII search can be skipped.
+    let ascii = raw.is_ascii();
+    let width_of = |line: &str| if ascii { line.len() } else { line.chars().count() };
+
+    let mut line_count = 0;
+    let mut trailing = (0, None);
+    let mut widths = Vec::new();
+    for (idx, line) in raw.lines().enumerate() {
+        line_count += 1;
+        if line.ends_with(char::is_whitespace) && !line.trim().is_empty() {
+            trailing = (trailing.0 + 1, trailing.1.or(Some(idx)));
+        }
+        if !line.is_empty() {
+            widths.push(width_of(line));
+        }
+    }
+
+    let blank = raw.lines().rev().take_while(|line| line.trim().is_empty()).count();
+    if blank > 0 && blank < line_count {
+        anomalies.push(Anomaly::TrailingBlankLines(blank));
+    }
+
+    if let (lines, Some(first)) = trailing {
+        anomalies.push(Anomaly::TrailingWhitespace { lines, first: first + 1 });
+    }
+
+    if !ascii {
+        let non_ascii = raw.lines()
+            .enumerate()
+            .find_map(|(idx, line)| line.chars().position(|c| !c.is_ascii()).map(|col| (idx, col)));
+        if let Some((line, col)) = non_ascii {
+            anomalies.push(Anomaly::NonAscii { line: line + 1, col: col + 1 });
+        }
+    }
+
+    // Only flag ragged widths when most lines agree, which is what a damaged grid looks like.
+    let (min, max) = widths.iter().copied().minmax().into_option().unwrap_or((0, 0));
+    if min != max && widths.len() >= 3 {
+        let counts = widths.iter().counts();
+        if let Some((&&width, &count)) = counts.iter().max_by_key(|(_, count)| **count)
+            && count * 4 >= widths.len() * 3 {
+            let first = raw.lines()
+                .position(|line| !line.is_empty() && width_of(line) != width)
+                .unwrap_or(0);
+            anomalies.push(Anomaly::RaggedWidths { width, min, max, first: first + 1 });
+        }
+    }
+
+    anomalies
+}
+
+impl<'a> IntoIterator for &'a AocInput {
+    type Item = &'a str;
+    type IntoIter = std::str::Lines<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.lines()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum AocOutput {
     Unimplemented,
     String(String),
@@ -212,3 +463,107 @@ impl From<String> for AocOutput {
         Self::String(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use test::Bencher;
+
+    #[test]
+    fn ints() {
+        let input = AocInput::new("p=0,4 v=3,-3\n10-20 x-5");
+        assert_eq!(input.line_ints::<i32>().expect("Parse"), vec![vec![0, 4, 3, -3], vec![10, 20, -5]]);
+        assert_eq!(input.ints::<i64>().expect("Parse").len(), 7);
+
+        let err = AocInput::new("1 2\n3 400").ints::<u8>().unwrap_err();
+        assert_eq!(err.to_string(), "line 2, column 3: cannot parse \"400\": number too large to fit in target type");
+    }
+
+    #[test]
+    fn lines() {
+        let input = AocInput::new("190: 10 19\n3267: 81 40 27");
+        assert_eq!(input.split_once_each(": ").expect("Split"), vec![("190", "10 19"), ("3267", "81 40 27")]);
+        assert!(input.split_once_each(" | ").is_err());
+        assert_eq!(AocInput::new("12\n-7").parse_lines::<i32>().expect("Parse"), vec![12, -7]);
+
+        let err = input.scan_lines::<(u64, String)>("{}: {} {}").unwrap_err();
+        assert_eq!(err.to_string(), "Scan pattern \"{}: {} {}\" has 3 fields, expected 2");
+        let err = input.scan_lines::<(u64, u64, u64)>("{}: {} {}").unwrap_err();
+        assert_eq!(err.to_string(), "line 2, column 10: cannot parse field 3 \"40 27\": invalid digit found in string");
+    }
+
+    #[test]
+    fn sections() {
+        let input = AocInput::new("47|53\n97|13\n\n\n75,47,61\n  \n97,61\n");
+        let sections = input.sections();
+        assert_eq!(sections.len(), 3);
+        assert_eq!(sections[0].raw(), "47|53\n97|13\n");
+
+        let [rules, updates] = AocInput::new("47|53\n\n75,47,61").sections_exact::<2>().expect("Sections");
+        assert_eq!(rules.raw(), "47|53\n");
+        assert_eq!(updates.raw(), "75,47,61\n");
+
+        let err = input.sections_exact::<2>().unwrap_err();
+        assert_eq!(err.to_string(), "expected 2 blank-line separated sections, found 3");
+    }
+
+    #[test]
+    fn normalize() {
+        let input = AocInput::new("\u{feff}..#\r\n .#\r\n#..");
+        assert_eq!(input.raw(), "..#\n .#\n#..\n");
+        assert_eq!(input.anomalies(), [Anomaly::ByteOrderMark, Anomaly::CarriageReturns, Anomaly::MissingFinalNewline]);
+
+        let input = AocInput::new("....\n... \n..\n....\n....\n\n");
+        assert_eq!(input.raw(), "....\n... \n..\n....\n....\n\n");
+        assert_eq!(input.anomalies(), [
+            Anomaly::TrailingBlankLines(1),
+            Anomaly::TrailingWhitespace { lines: 1, first: 2 },
+            Anomaly::RaggedWidths { width: 4, min: 2, max: 4, first: 3 },
+        ]);
+
+        assert_eq!(AocInput::new("1 2 3\n40 50\n6\n").anomalies(), []);
+        assert_eq!(AocInput::new("a\nb\u{e9}\n").anomalies(), [Anomaly::NonAscii { line: 2, col: 2 }]);
+    }
+
+    #[test]
+    fn dedent() {
+        let input = AocInput::dedent("....#
+                                      .#..
+                                        ^.");
+        assert_eq!(input.raw(), "....#\n.#..\n  ^.\n");
+
+        let input = AocInput::dedent("
+            ###
+              #
+
+            # #
+        ");
+        assert_eq!(input.raw(), "###\n  #\n\n# #\n");
+        assert_eq!(input.anomalies(), []);
+    }
+
+    #[test]
+    fn columns() {
+        let input = AocInput::new("3   4\n4   3\n2   5");
+        assert_eq!(input.columns::<i32>().expect("Parse"), vec![vec![3, 4, 2], vec![4, 3, 5]]);
+
+        let err = AocInput::new("3   4\n4   x").columns::<i32>().unwrap_err();
+        assert_eq!(err.to_string(), "line 2, column 5: cannot parse \"x\": invalid digit found in string");
+        assert!(AocInput::new("3 4\n5").columns::<i32>().is_err());
+    }
+
+    #[bench]
+    fn bench_new_and_lines(b: &mut Bencher) {
+        let text = large_input();
+        b.iter(|| {
+            let input = AocInput::new(text.as_str());
+            input.lines().map(|line| line.len()).sum::<usize>()
+        });
+    }
+
+    fn large_input() -> String {
+        (0..10_000).map(|i| format!("{:>8} {:>8} {:>8} {:>8}\n", i, i * 7, i * 13, i * 31)).collect()
+    }
+
+}
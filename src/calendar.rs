@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::time::Duration;
+use anyhow::Error;
+use regex::Regex;
+use crate::aocday::AocDay;
+use crate::client::{self, AocClient, CachePolicy};
+use crate::puzzle::Puzzle;
+use crate::YEAR;
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Code {
+    Missing,
+    Stub,
+    Partial,
+    Implemented,
+}
+
+#[derive(Clone, Debug)]
+pub struct CalendarDay {
+    pub day: u8,
+    pub name: Option<String>,
+    pub code: Code,
+    pub answers: Vec<String>,
+    pub stars: usize,
+}
+
+// Puzzle pages come from `puzzle`, usually Puzzle::cached, so tests can run
+// without whatever is in the local cache.
+pub fn build(days: &[Box<dyn AocDay>], last_day: u8, events: Option<&str>, puzzle: impl Fn(u8) -> Option<Puzzle>) -> Vec<CalendarDay> {
+    let mut registered: HashMap<u8, (String, Code)> = days.iter()
+        .map(|day| {
+            let (num, name) = day.info().into();
+            let code = match day.implemented_parts() {
+                0 => Code::Stub,
+                1 => Code::Partial,
+                _ => Code::Implemented,
+            };
+            (num, (name, code))
+        })
+        .collect();
+    let event_stars = events.map(parse_stars).unwrap_or_default();
+
+    (1..=last_day)
+        .map(|day| {
+            let puzzle = puzzle(day);
+            let (name, code) = registered.remove(&day).unwrap_or((String::new(), Code::Missing));
+            let name = Some(name)
+                .filter(|name| !name.is_empty())
+                .or_else(|| puzzle.as_ref().and_then(|p| p.title().map(str::to_string)));
+            let answers = puzzle.map(|p| p.answers().to_vec()).unwrap_or_default();
+            let stars = event_stars.get(&day).copied().unwrap_or(answers.len());
+            CalendarDay { day, name, code, answers, stars }
+        })
+        .collect()
+}
+
+pub fn events_page(client: Option<&AocClient>) -> Result<Option<String>, Error> {
+    let path = format!("/{YEAR}");
+    match client {
+        Some(client) => Ok(Some(client.get(path.as_str(), CachePolicy::MaxAge(REFRESH_INTERVAL))?.body)),
+        None => Ok(client::cached(path.as_str()).map(|response| response.body)),
+    }
+}
+
+pub fn render(calendar: &[CalendarDay], redact: impl Fn(String) -> String) -> String {
+    let width = calendar.iter()
+        .filter_map(|day| day.name.as_ref().map(String::len))
+        .max()
+        .unwrap_or(0);
+    let total: usize = calendar.iter().map(|day| day.stars).sum();
+
+    let mut out = String::new();
+    writeln!(out, "Advent of Code {YEAR}  ({total} stars)").unwrap();
+    for day in calendar {
+        let stars = match day.stars {
+            0 => "..",
+            1 => "*.",
+            _ => "**",
+        };
+        let code = match day.code {
+            Code::Missing => "-",
+            Code::Stub => "stub",
+            Code::Partial => "part 1",
+            Code::Implemented => "code",
+        };
+        let answers = day.answers.iter()
+            .map(|answer| redact(answer.clone()))
+            .collect::<Vec<_>>()
+            .join(" / ");
+        let line = format!("{:>2} {stars} {:<width$}  {code:<6}  {answers}", day.day, day.name.as_deref().unwrap_or(""));
+        writeln!(out, "{}", line.trim_end()).unwrap();
+    }
+    out
+}
+
+fn parse_stars(html: &str) -> HashMap<u8, usize> {
+    let regex = Regex::new(r#"aria-label="Day (\d+)(?:, (one star|two stars))?""#).unwrap();
+    regex.captures_iter(html)
+        .filter_map(|c| {
+            let day = c[1].parse().ok()?;
+            let stars = match c.get(2).map(|m| m.as_str()) {
+                Some("two stars") => 2,
+                Some(_) => 1,
+                None => 0,
+            };
+            Some((day, stars))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::days::build_days;
+
+    #[test]
+    fn stars() {
+        let html = r#"<a aria-label="Day 1, two stars" href="/2024/day/1" class="calendar-day1 calendar-verycomplete">
+<a aria-label="Day 2, one star" href="/2024/day/2" class="calendar-day2 calendar-complete">
+<a aria-label="Day 3" href="/2024/day/3" class="calendar-day3">"#;
+        assert_eq!(parse_stars(html), HashMap::from([(1, 2), (2, 1), (3, 0)]));
+    }
+
+    #[test]
+    fn stubs() {
+        let calendar = build(&build_days(), 12, None, |_| None);
+        assert_eq!(calendar[3].code, Code::Stub);
+        assert_eq!(calendar[4].code, Code::Stub);
+        assert_eq!(calendar[5].code, Code::Implemented);
+        assert_eq!(calendar[8].code, Code::Partial);
+        assert_eq!(calendar[9].code, Code::Missing);
+        assert!(calendar.iter().all(|day| day.answers.is_empty() && day.stars == 0));
+    }
+
+}
@@ -0,0 +1,315 @@
+use std::{env, fs, thread};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use anyhow::{anyhow, Error};
+use chrono::{DateTime, Utc};
+use reqwest::blocking::Client;
+use crate::session::SessionToken;
+
+const BASE_URL: &str = "https://adventofcode.com";
+const DEFAULT_USER_AGENT: &str = "github.com/ryantenney/advent-of-code-2024-rust by ryan@10e.us";
+const DEFAULT_CACHE_DIR: &str = ".aoc_cache";
+const MIN_INTERVAL: Duration = Duration::from_secs(5);
+const LAST_REQUEST_FILE: &str = ".last_request";
+const BODY_SUFFIX: &str = ".cache";
+const FETCHED_SUFFIX: &str = ".fetched";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CachePolicy {
+    Never,
+    MaxAge(Duration),
+    Forever,
+}
+
+#[derive(Clone, Debug)]
+pub struct Response {
+    pub body: String,
+    pub fetched: DateTime<Utc>,
+    pub cached: bool,
+}
+
+#[derive(Debug)]
+pub struct AocClient {
+    client: Client,
+    session: SessionToken,
+    user_agent: String,
+    cache_dir: PathBuf,
+    min_interval: Duration,
+}
+
+impl AocClient {
+
+    pub fn new() -> Result<Self, Error> {
+        Ok(Self {
+            client: Client::new(),
+            session: SessionToken::load()?,
+            user_agent: env::var("AOC_USER_AGENT").unwrap_or_else(|_| DEFAULT_USER_AGENT.to_string()),
+            cache_dir: cache_dir()?,
+            min_interval: MIN_INTERVAL,
+        })
+    }
+
+    pub fn with_user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = user_agent.to_string();
+        self
+    }
+
+    pub fn with_min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = min_interval.max(MIN_INTERVAL);
+        self
+    }
+
+    pub fn session(&self) -> &SessionToken {
+        &self.session
+    }
+
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    pub fn get(&self, path: &str, policy: CachePolicy) -> Result<Response, Error> {
+        let cache_path = self.cache_path(path);
+
+        if let Some(cached) = read_cached(&cache_path, policy) {
+            return Ok(cached);
+        }
+
+        self.throttle()?;
+        let response = self.client.get(format!("{BASE_URL}{path}"))
+            .header("Cookie", self.session.cookie())
+            .header("User-Agent", self.user_agent.as_str())
+            .send()
+            .map_err(|e| anyhow!("GET {path} failed: {}", e.without_url()))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow!("GET {path} failed: {status}"));
+        }
+
+        let response = Response {
+            body: response.text()?,
+            fetched: Utc::now(),
+            cached: false,
+        };
+
+        if policy != CachePolicy::Never {
+            write_cached(&cache_path, &response)?;
+        }
+
+        Ok(response)
+    }
+
+    pub fn cached(&self, path: &str) -> Option<Response> {
+        read_cached(&self.cache_path(path), CachePolicy::Forever)
+    }
+
+    pub fn invalidate(&self, path: &str) -> Result<(), Error> {
+        let cache_path = self.cache_path(path);
+        if cache_path.exists() {
+            fs::remove_file(&cache_path)?;
+        }
+        let fetched_path = fetched_path(&cache_path);
+        if fetched_path.exists() {
+            fs::remove_file(fetched_path)?;
+        }
+        Ok(())
+    }
+
+    fn cache_path(&self, path: &str) -> PathBuf {
+        cache_path(&self.cache_dir, path)
+    }
+
+    fn throttle(&self) -> Result<(), Error> {
+        let path = self.cache_dir.join(LAST_REQUEST_FILE);
+
+        let last_request = fs::read_to_string(&path).ok()
+            .and_then(|s| DateTime::parse_from_rfc3339(s.trim()).ok())
+            .map(|t| t.with_timezone(&Utc));
+
+        if let Some(last_request) = last_request {
+            let wait = match (Utc::now() - last_request).to_std() {
+                Ok(elapsed) => self.min_interval.saturating_sub(elapsed),
+                Err(_) => self.min_interval,
+            };
+            if !wait.is_zero() {
+                thread::sleep(wait);
+            }
+        }
+
+        fs::create_dir_all(&self.cache_dir)?;
+        fs::write(path, Utc::now().to_rfc3339())?;
+        Ok(())
+    }
+
+}
+
+pub fn cached(path: &str) -> Option<Response> {
+    cached_in(&cache_dir().ok()?, path)
+}
+
+fn cached_in(cache_dir: &Path, path: &str) -> Option<Response> {
+    read_cached(&cache_path(cache_dir, path), CachePolicy::Forever)
+}
+
+pub fn cache_dir() -> Result<PathBuf, Error> {
+    match env::var_os("AOC_CACHE_DIR") {
+        Some(dir) => Ok(PathBuf::from(dir)),
+        None => Ok(env::current_dir()?.join(DEFAULT_CACHE_DIR)),
+    }
+}
+
+// Keeps the cache inside cache_dir whatever the request path: empty, "." and
+// ".." segments are dropped and anything unusual in a segment becomes '_'.
+fn cache_path(cache_dir: &Path, path: &str) -> PathBuf {
+    let relative = path.split('/')
+        .filter(|segment| !matches!(*segment, "" | "." | ".."))
+        .map(|segment| segment.chars()
+            .map(|c| if c.is_ascii_alphanumeric() || "._-".contains(c) { c } else { '_' })
+            .collect::<String>())
+        .collect::<Vec<_>>()
+        .join("/");
+    cache_dir.join(format!("{relative}{BODY_SUFFIX}"))
+}
+
+fn fetched_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(FETCHED_SUFFIX);
+    path.with_file_name(name)
+}
+
+fn read_cached(path: &Path, policy: CachePolicy) -> Option<Response> {
+    if policy == CachePolicy::Never {
+        return None;
+    }
+
+    let fetched = fs::read_to_string(fetched_path(path)).ok()
+        .and_then(|s| DateTime::parse_from_rfc3339(s.trim()).ok())?
+        .with_timezone(&Utc);
+
+    if let CachePolicy::MaxAge(max_age) = policy {
+        let age = (Utc::now() - fetched).to_std().unwrap_or_default();
+        if age > max_age {
+            return None;
+        }
+    }
+
+    let body = fs::read_to_string(path).ok()?;
+    Some(Response { body, fetched, cached: true })
+}
+
+fn write_cached(path: &Path, response: &Response) -> Result<(), Error> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, &response.body)?;
+    fs::write(fetched_path(path), response.fetched.to_rfc3339())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::process;
+    use std::time::Instant;
+    use crate::session::TokenSource;
+    use super::*;
+
+    fn temp_cache_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-client-{}-{name}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn client(cache_dir: &Path, min_interval: Duration) -> AocClient {
+        AocClient {
+            client: Client::new(),
+            session: SessionToken::parse("53616c7465645f5f", TokenSource::Env).expect("Token"),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            cache_dir: cache_dir.to_path_buf(),
+            min_interval,
+        }
+    }
+
+    fn response(body: &str, age: chrono::Duration) -> Response {
+        Response { body: body.to_string(), fetched: Utc::now() - age, cached: false }
+    }
+
+    #[test]
+    fn sanitises_cache_paths() {
+        let dir = Path::new("/cache");
+        assert_eq!(cache_path(dir, "/2024/day/6"), dir.join("2024/day/6.cache"));
+        assert_eq!(cache_path(dir, "/2024/day/6/input"), dir.join("2024/day/6/input.cache"));
+        assert_eq!(cache_path(dir, "/2024/leaderboard/private/view/123.json"), dir.join("2024/leaderboard/private/view/123.json.cache"));
+        assert_eq!(cache_path(dir, "/../../etc/passwd"), dir.join("etc/passwd.cache"));
+        assert_eq!(cache_path(dir, "//a/./b?c=d&e"), dir.join("a/b_c_d_e.cache"));
+    }
+
+    #[test]
+    fn cache_policies() {
+        let dir = temp_cache_dir("policies");
+        let path = cache_path(&dir, "/2024/day/1");
+        write_cached(&path, &response("fresh", chrono::Duration::minutes(1))).expect("Write");
+
+        let cached = read_cached(&path, CachePolicy::Forever).expect("Forever");
+        assert_eq!((cached.body.as_str(), cached.cached), ("fresh", true));
+        assert!(read_cached(&path, CachePolicy::MaxAge(Duration::from_secs(300))).is_some());
+        assert!(read_cached(&path, CachePolicy::MaxAge(Duration::from_secs(30))).is_none());
+        assert!(read_cached(&path, CachePolicy::Never).is_none());
+
+        write_cached(&path, &response("stale", chrono::Duration::days(30))).expect("Write");
+        assert!(read_cached(&path, CachePolicy::MaxAge(Duration::from_secs(900))).is_none());
+        assert_eq!(read_cached(&path, CachePolicy::Forever).expect("Forever").body, "stale");
+
+        fs::remove_file(fetched_path(&path)).expect("Remove");
+        assert!(read_cached(&path, CachePolicy::Forever).is_none());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn invalidate() {
+        let dir = temp_cache_dir("invalidate");
+        let client = client(&dir, MIN_INTERVAL);
+        let path = client.cache_path("/2024/day/2");
+        write_cached(&path, &response("page", chrono::Duration::zero())).expect("Write");
+        assert_eq!(client.cached("/2024/day/2").expect("Cached").body, "page");
+
+        client.invalidate("/2024/day/2").expect("Invalidate");
+        assert!(client.cached("/2024/day/2").is_none());
+        assert!(!path.exists() && !fetched_path(&path).exists());
+        client.invalidate("/2024/day/2").expect("Invalidate missing");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn throttle_persists_across_runs() {
+        let dir = temp_cache_dir("throttle");
+        let interval = Duration::from_millis(200);
+
+        let start = Instant::now();
+        client(&dir, interval).throttle().expect("First");
+        assert!(start.elapsed() < interval);
+        let stamp = fs::read_to_string(dir.join(LAST_REQUEST_FILE)).expect("Timestamp");
+        assert!(DateTime::parse_from_rfc3339(stamp.trim()).is_ok());
+
+        // A fresh client stands in for the next run of the binary.
+        let start = Instant::now();
+        client(&dir, interval).throttle().expect("Second");
+        assert!(start.elapsed() >= interval / 2);
+
+        fs::write(dir.join(LAST_REQUEST_FILE), (Utc::now() - chrono::Duration::seconds(10)).to_rfc3339()).unwrap();
+        let start = Instant::now();
+        client(&dir, interval).throttle().expect("Third");
+        assert!(start.elapsed() < interval);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn cached_in_dir() {
+        let dir = temp_cache_dir("cached");
+        write_cached(&cache_path(&dir, "/2024/day/3"), &response("page", chrono::Duration::days(30))).expect("Write");
+        assert_eq!(cached_in(&dir, "/2024/day/3").expect("Cached").body, "page");
+        assert!(cached_in(&dir, "/2024/day/4").is_none());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+}
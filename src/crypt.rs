@@ -0,0 +1,201 @@
+use std::{env, fs, io};
+use std::fmt::{Debug, Formatter};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use anyhow::{anyhow, Error};
+use chacha20poly1305::{Key, KeyInit, XChaCha20Poly1305, XNonce};
+use chacha20poly1305::aead::{Aead, AeadCore, OsRng};
+use crate::session::{check_permissions, config_dir};
+
+const KEY_ENV: &str = "AOC_INPUT_KEY";
+const KEY_FILE: &str = "input_key";
+const INPUT_DIR: &str = "inputs";
+const NONCE_LEN: usize = 24;
+
+#[derive(Clone)]
+pub struct InputKey {
+    key: Key,
+}
+
+impl InputKey {
+
+    pub fn load() -> Result<Option<Self>, Error> {
+        if let Ok(key) = env::var(KEY_ENV) {
+            return Self::parse(key.as_str()).map(Some);
+        }
+
+        match key_path() {
+            Some(path) if path.exists() => {
+                check_permissions(&path)?;
+                Self::parse(fs::read_to_string(&path)?.as_str()).map(Some)
+            },
+            _ => Ok(None),
+        }
+    }
+
+    pub fn generate() -> Self {
+        Self { key: XChaCha20Poly1305::generate_key(&mut OsRng) }
+    }
+
+    pub fn save(&self) -> Result<PathBuf, Error> {
+        let path = key_path().ok_or_else(|| anyhow!("No config directory for {KEY_FILE}"))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = create_key_file(&path).map_err(|e| match e.kind() {
+            io::ErrorKind::AlreadyExists => anyhow!("Refusing to overwrite {}", path.display()),
+            _ => e.into(),
+        })?;
+        file.write_all(self.to_hex().as_bytes())?;
+        Ok(path)
+    }
+
+    fn parse(raw: &str) -> Result<Self, Error> {
+        let raw = raw.trim();
+        if raw.len() != 64 || !raw.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(anyhow!("Input key must be 64 hex characters"));
+        }
+        let bytes: Vec<u8> = (0..raw.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&raw[i..i + 2], 16).unwrap())
+            .collect();
+        Ok(Self { key: *Key::from_slice(&bytes) })
+    }
+
+    pub fn to_hex(&self) -> String {
+        self.key.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    pub fn encrypt(&self, plaintext: &str) -> Result<Vec<u8>, Error> {
+        let cipher = XChaCha20Poly1305::new(&self.key);
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher.encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|_| anyhow!("Encryption failed"))?;
+
+        let mut out = nonce.to_vec();
+        out.extend(ciphertext);
+        Ok(out)
+    }
+
+    pub fn decrypt(&self, data: &[u8]) -> Result<String, Error> {
+        if data.len() < NONCE_LEN {
+            return Err(anyhow!("Encrypted input is truncated"));
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        let plaintext = XChaCha20Poly1305::new(&self.key)
+            .decrypt(XNonce::from_slice(nonce), ciphertext)
+            .map_err(|_| anyhow!("Unable to decrypt input: wrong key or corrupted file"))?;
+        Ok(String::from_utf8(plaintext)?)
+    }
+
+}
+
+impl Debug for InputKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("InputKey(<redacted>)")
+    }
+}
+
+pub fn encrypted_input_path(day: u8) -> Result<PathBuf, Error> {
+    Ok(env::current_dir()?.join(INPUT_DIR).join(format!("day{day}.txt.enc")))
+}
+
+pub fn read_encrypted_input(day: u8) -> Result<Option<String>, Error> {
+    let path = encrypted_input_path(day)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    match InputKey::load()? {
+        Some(key) => key.decrypt(fs::read(&path)?.as_slice())
+            .map(Some)
+            .map_err(|e| anyhow!("{}: {e}", path.display())),
+        None => Ok(None),
+    }
+}
+
+// None when the existing file already decrypts to `input`. Each encryption
+// picks a fresh nonce, so rewriting it would show as a change in git.
+pub fn write_encrypted_input(key: &InputKey, day: u8, input: &str) -> Result<Option<PathBuf>, Error> {
+    let path = encrypted_input_path(day)?;
+    Ok(write_encrypted(key, &path, input)?.then_some(path))
+}
+
+fn write_encrypted(key: &InputKey, path: &Path, input: &str) -> Result<bool, Error> {
+    if let Ok(existing) = fs::read(path)
+        && key.decrypt(existing.as_slice()).is_ok_and(|decrypted| decrypted == input) {
+        return Ok(false);
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, key.encrypt(input)?)?;
+    Ok(true)
+}
+
+fn key_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(KEY_FILE))
+}
+
+// Created owner-only from the start, so the key is never readable by others,
+// and create_new refuses to replace a key the team may already share.
+#[cfg(unix)]
+fn create_key_file(path: &Path) -> io::Result<File> {
+    use std::os::unix::fs::OpenOptionsExt;
+    OpenOptions::new().write(true).create_new(true).mode(0o600).open(path)
+}
+
+#[cfg(not(unix))]
+fn create_key_file(path: &Path) -> io::Result<File> {
+    OpenOptions::new().write(true).create_new(true).open(path)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let key = InputKey::generate();
+        let encrypted = key.encrypt("3   4\n4   3\n").expect("Encrypt");
+        assert_eq!(key.decrypt(&encrypted).expect("Decrypt"), "3   4\n4   3\n");
+        assert!(InputKey::generate().decrypt(&encrypted).is_err());
+
+        let parsed = InputKey::parse(key.to_hex().as_str()).expect("Parse");
+        assert_eq!(parsed.to_hex(), key.to_hex());
+        assert!(!format!("{key:?}").contains(key.to_hex().as_str()));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn key_file_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = env::temp_dir().join(format!("aoc-key-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(KEY_FILE);
+
+        create_key_file(&path).expect("Create");
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        assert_eq!(create_key_file(&path).unwrap_err().kind(), io::ErrorKind::AlreadyExists);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn rewrites_only_changed_inputs() {
+        let dir = env::temp_dir().join(format!("aoc-crypt-{}", std::process::id()));
+        let path = dir.join("day1.txt.enc");
+        let key = InputKey::generate();
+
+        assert!(write_encrypted(&key, &path, "1\n").expect("Write"));
+        let written = fs::read(&path).unwrap();
+        assert!(!write_encrypted(&key, &path, "1\n").expect("Unchanged"));
+        assert_eq!(fs::read(&path).unwrap(), written);
+
+        assert!(write_encrypted(&key, &path, "2\n").expect("Changed"));
+        assert_eq!(key.decrypt(&fs::read(&path).unwrap()).expect("Decrypt"), "2\n");
+        assert!(write_encrypted(&InputKey::generate(), &path, "2\n").expect("New key"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+}
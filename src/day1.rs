@@ -1,7 +1,6 @@
 use std::cmp::{max, min};
 use anyhow::Error;
 use itertools::Itertools;
-use regex::Regex;
 use crate::aocday::{AocDay, AocInfo, AocInput, AocOutput};
 
 #[derive(Default)]
@@ -27,23 +26,15 @@ impl AocDay for Today {
     }
 
     fn init(&mut self, input: AocInput) -> Result<(), Error> {
-        let regex = Regex::new(r"\s+")?;
-        let input = input.lines().iter()
-            .map(|s| {
-                regex.split(s)
-                    .take(2)
-                    .filter_map(|part| part.parse::<i32>().ok())
-                    .collect_vec()
-            })
-            .collect_vec();
-
-        self.left = input.iter()
-            .filter_map(|v| v.get(0).copied())
+        let mut columns = input.columns::<i32>()?.into_iter();
+
+        self.left = columns.next().unwrap_or_default()
+            .into_iter()
             .sorted()
             .collect();
 
-        self.right = input.iter()
-            .filter_map(|v| v.get(1).copied())
+        self.right = columns.next().unwrap_or_default()
+            .into_iter()
             .sorted()
             .collect();
 
@@ -114,7 +105,7 @@ mod tests {
 
     fn init(input: &str) -> Today {
         let mut day = Today::new();
-        day.init(AocInput::new(input).trim()).expect("Init failed");
+        day.init(AocInput::dedent(input)).expect("Init failed");
         day
     }
 
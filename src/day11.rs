@@ -229,7 +229,7 @@ mod tests {
 
     fn init(input: &str) -> Today {
         let mut day = Today::new();
-        day.init(AocInput::new(input).trim()).expect("Init failed");
+        day.init(AocInput::dedent(input)).expect("Init failed");
         day
     }
 
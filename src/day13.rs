@@ -1,6 +1,5 @@
 use anyhow::{anyhow, Error};
 use itertools::Itertools;
-use regex::Regex;
 use crate::aocday::{AocDay, AocInfo, AocInput, AocOutput};
 use crate::aocday::AocOutput::Unimplemented;
 
@@ -26,20 +25,18 @@ impl AocDay for Today {
     }
 
     fn init(&mut self, input: AocInput) -> Result<(), Error> {
-        let regex = Regex::new(r"\d+")?;
-        self.claw_machines = input.lines().into_iter()
-            .filter(|s| !s.is_empty())
-            .filter_map(|s| {
-                regex.find_iter(s.as_str())
-                    .filter_map(|m| {
-                        m.as_str().parse::<isize>().ok()
-                    })
-                    .collect_tuple::<(_, _)>()
-                    .and_then(|(x, y)| Some(XY::new(x, y)))
+        self.claw_machines = input.sections()
+            .iter()
+            .map(|section| {
+                let (a, b, prize) = section.ints::<isize>()?
+                    .into_iter()
+                    .tuples()
+                    .map(|(x, y)| XY::new(x, y))
+                    .collect_tuple()
+                    .ok_or_else(|| anyhow!("Expected button A, button B and prize in {:?}", section.raw()))?;
+                Ok(ClawMachine::new(a, b, prize))
             })
-            .tuples()
-            .map(|(a, b, prize)| ClawMachine::new(a, b, prize))
-            .collect_vec();
+            .collect::<Result<_, Error>>()?;
 
         Ok(())
     }
@@ -165,7 +162,7 @@ mod tests {
 
     fn init(input: &str) -> Today {
         let mut day = Today::new();
-        day.init(AocInput::new(input).trim()).expect("Init failed");
+        day.init(AocInput::dedent(input)).expect("Init failed");
         day
     }
 
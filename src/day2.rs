@@ -24,12 +24,7 @@ impl AocDay for Today {
     }
 
     fn init(&mut self, input: AocInput) -> Result<(), Error> {
-        self.reports = input.lines().iter()
-            .map(|s| {
-                s.split(' ')
-                    .filter_map(|n| n.parse().ok())
-                    .collect()
-            }).collect();
+        self.reports = input.line_ints()?;
 
         Ok(())
     }
@@ -120,7 +115,7 @@ mod tests {
 
     fn init(input: &str) -> Today {
         let mut day = Today::new();
-        day.init(AocInput::new(input).trim()).expect("Init failed");
+        day.init(AocInput::dedent(input)).expect("Init failed");
         day
     }
 
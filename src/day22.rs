@@ -27,9 +27,8 @@ impl AocDay for Today {
     }
 
     fn init(&mut self, input: AocInput) -> Result<(), Error> {
-        self.buyers = input.lines()
-            .iter()
-            .filter_map(|s| s.parse().ok())
+        self.buyers = input.parse_lines()?
+            .into_iter()
             .map(|seed| {
                 let mut buyer = Buyer::new(seed);
                 buyer.rounds(2000);
@@ -242,7 +241,7 @@ mod tests {
 
     fn init(input: &str) -> Today {
         let mut day = Today::new();
-        day.init(AocInput::new(input).trim()).expect("Init failed");
+        day.init(AocInput::dedent(input)).expect("Init failed");
         day
     }
 
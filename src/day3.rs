@@ -28,7 +28,7 @@ impl AocDay for Today {
     }
 
     fn init(&mut self, input: AocInput) -> Result<(), Error> {
-        self.parser.set(Parser::new(input.raw().as_str(), vec!["mul(", "do()", "don't()"])).expect("TODO: panic message");
+        self.parser.set(Parser::new(input.raw(), vec!["mul(", "do()", "don't()"])).expect("TODO: panic message");
 
         Ok(())
     }
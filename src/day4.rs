@@ -34,6 +34,10 @@ impl AocDay for Today {
         Ok(Unimplemented)
     }
 
+    fn implemented_parts(&self) -> u8 {
+        0
+    }
+
 }
 
 #[cfg(test)]
@@ -52,7 +56,7 @@ mod tests {
 
     fn init(input: &str) -> Today {
         let mut day = Today::new();
-        day.init(AocInput::new(input).trim()).expect("Init failed");
+        day.init(AocInput::dedent(input)).expect("Init failed");
         day
     }
 
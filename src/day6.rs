@@ -3,7 +3,7 @@ use std::ops::AddAssign;
 use anyhow::{anyhow, Error};
 use crate::aocday::{AocDay, AocInfo, AocInput, AocOutput};
 use crate::aocday::AocOutput::Unimplemented;
-use crate::grid::{Direction, Grid, Position, ToVector};
+use crate::grid::{Direction, Grid, Markers, Position, ToVector};
 use crate::grid::Direction::Up;
 
 #[derive(Default)]
@@ -28,12 +28,9 @@ impl AocDay for Today {
     }
 
     fn init(&mut self, input: AocInput) -> Result<(), Error> {
-        self.grid.init(&input.lines());
-
-        if let Some((pos, _)) = self.grid.enumerate().filter(|(_, chr)| **chr == '^').next() {
-            self.init_pos = pos;
-            self.grid.set(pos, '.');
-        }
+        let (grid, markers) = Grid::parse_markers(input.lines(), Markers::new().required("^"), '.')?;
+        self.grid = grid;
+        self.init_pos = markers.get('^')?.pos;
 
         Ok(())
     }
@@ -129,6 +126,7 @@ fn run_cycle_check(grid: &Grid<char>, pos: Position, dir: &Direction) -> bool {
 mod tests {
 
     use super::*;
+    use test::Bencher;
 
     const EX: &str = "....#.....
                       .........#
@@ -148,9 +146,55 @@ mod tests {
         assert_eq!(day.part2().expect("Part 2"), 6.into());
     }
 
+    #[bench]
+    fn bench_init(b: &mut Bencher) {
+        let input = AocInput::new(large_input().as_str());
+        b.iter(|| {
+            let mut day = Today::new();
+            day.init(input.clone()).expect("Init failed");
+            day
+        });
+    }
+
+    #[bench]
+    fn bench_part1(b: &mut Bencher) {
+        let day = init(large_input().as_str());
+        b.iter(|| day.part1());
+    }
+
+    #[bench]
+    fn bench_part2(b: &mut Bencher) {
+        let day = init(EX);
+        b.iter(|| day.part2());
+    }
+
+    #[bench]
+    fn bench_part2_large(b: &mut Bencher) {
+        let day = init(large_input().as_str());
+        b.iter(|| day.part2());
+    }
+
+    fn large_input() -> String {
+        let size = 130;
+        let mut seed = 0x2024u32;
+        let mut input = String::new();
+        for y in 0..size {
+            for x in 0..size {
+                seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+                input.push(match (x, y) {
+                    (65, 65) => '^',
+                    _ if (seed >> 16) % 100 == 0 => '#',
+                    _ => '.',
+                });
+            }
+            input.push('\n');
+        }
+        input
+    }
+
     fn init(input: &str) -> Today {
         let mut day = Today::new();
-        day.init(AocInput::new(input).trim()).expect("Init failed");
+        day.init(AocInput::dedent(input)).expect("Init failed");
         day
     }
 
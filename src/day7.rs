@@ -26,13 +26,13 @@ impl AocDay for Today {
     }
 
     fn init(&mut self, input: AocInput) -> Result<(), Error> {
-        self.input = input.lines().iter().map(|line| {
-            let output = line.split(": ").take(2).collect_vec();
-            let result = output[0].parse::<u64>().unwrap();
-            let values = output[1].split(" ").filter_map(|s| s.parse::<u64>().ok()).collect_vec();
-            TestData { result, values }
-        })
-        .collect();
+        self.input = input.split_once_each(": ")?
+            .into_iter()
+            .map(|(result, values)| Ok(TestData {
+                result: result.parse()?,
+                values: values.split(' ').map(str::parse).collect::<Result<_, _>>()?,
+            }))
+            .collect::<Result<_, Error>>()?;
 
         Ok(())
     }
@@ -163,7 +163,7 @@ mod tests {
 
     fn init(input: &str) -> Today {
         let mut day = Today::new();
-        day.init(AocInput::new(input).trim()).expect("Init failed");
+        day.init(AocInput::dedent(input)).expect("Init failed");
         day
     }
 
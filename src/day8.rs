@@ -29,7 +29,7 @@ impl AocDay for Today {
     }
 
     fn init(&mut self, input: AocInput) -> Result<(), Error> {
-        self.grid.init(&input.lines());
+        self.grid = Grid::parse(input.lines())?;
 
         self.antennas_by_freq = self.grid.enumerate()
             .filter(|(_, ant)| ant.freq != '.')
@@ -170,6 +170,7 @@ impl Default for AntennaData {
 mod tests {
 
     use super::*;
+    use test::Bencher;
 
     const EX: &str = "............
                       ........0...
@@ -191,9 +192,31 @@ mod tests {
         assert_eq!(day.part2().expect("Part 2"), 34.into());
     }
 
+    #[bench]
+    fn bench_init(b: &mut Bencher) {
+        let input = AocInput::dedent(EX);
+        b.iter(|| {
+            let mut day = Today::new();
+            day.init(input.clone()).expect("Init failed");
+            day
+        });
+    }
+
+    #[bench]
+    fn bench_part1(b: &mut Bencher) {
+        let day = init(EX);
+        b.iter(|| day.part1());
+    }
+
+    #[bench]
+    fn bench_part2(b: &mut Bencher) {
+        let day = init(EX);
+        b.iter(|| day.part2());
+    }
+
     fn init(input: &str) -> Today {
         let mut day = Today::new();
-        day.init(AocInput::new(input).trim()).expect("Init failed");
+        day.init(AocInput::dedent(input)).expect("Init failed");
         day
     }
 
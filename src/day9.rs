@@ -72,6 +72,10 @@ impl AocDay for Today {
         Ok(Unimplemented)
     }
 
+    fn implemented_parts(&self) -> u8 {
+        1
+    }
+
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -219,7 +223,7 @@ mod tests {
 
     fn init(input: &str) -> Today {
         let mut day = Today::new();
-        day.init(AocInput::new(input).trim()).expect("Init failed");
+        day.init(AocInput::dedent(input)).expect("Init failed");
         day
     }
 
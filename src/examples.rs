@@ -0,0 +1,238 @@
+use std::{env, fs};
+use std::path::{Path, PathBuf};
+use anyhow::{anyhow, Error};
+use regex::Regex;
+use crate::aocday::AocInput;
+use crate::html;
+use crate::puzzle::Puzzle;
+
+const FIXTURE_DIR: &str = "fixtures";
+const ANSWERS_FILE: &str = "answers.txt";
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Example {
+    pub input: String,
+    pub answers: [Option<String>; 2],
+}
+
+impl Example {
+    fn new(input: String) -> Self {
+        Self { input, answers: [None, None] }
+    }
+
+    pub fn input(&self) -> AocInput {
+        AocInput::new(self.input.as_str())
+    }
+
+    pub fn answer(&self, part: u8) -> Option<&str> {
+        (part as usize).checked_sub(1)
+            .and_then(|index| self.answers.get(index))
+            .and_then(|answer| answer.as_deref())
+    }
+}
+
+pub fn extract(html: &str) -> Vec<Example> {
+    let puzzle = Puzzle::parse(0, html);
+    let mut examples: Vec<Example> = Vec::new();
+    let mut part1_example = None;
+
+    for (part, article) in puzzle.parts().iter().enumerate().take(2) {
+        let mut introduced_example = None;
+        let mut first_example = None;
+        for (idx, (input, introduced)) in pre_blocks(article).into_iter().enumerate() {
+            let first = part == 0 && idx == 0;
+            if !introduced && !first {
+                continue;
+            }
+            let index = match examples.iter().position(|example| example.input == input) {
+                Some(index) => index,
+                None => {
+                    examples.push(Example::new(input));
+                    examples.len() - 1
+                },
+            };
+            if introduced {
+                introduced_example = introduced_example.or(Some(index));
+            } else {
+                first_example = Some(index);
+            }
+        }
+
+        let chosen = introduced_example.or(first_example).or(part1_example);
+        if part == 0 {
+            part1_example = chosen;
+        }
+
+        if let (Some(index), Some(answer)) = (chosen, last_answer(article)) {
+            examples[index].answers[part] = Some(answer);
+        }
+    }
+
+    examples
+}
+
+fn pre_blocks(article: &str) -> Vec<(String, bool)> {
+    let regex = Regex::new(r"(?s)<pre><code>(.*?)</code></pre>").unwrap();
+    regex.captures_iter(article)
+        .map(|captures| {
+            let start = captures.get(0).unwrap().start();
+            let intro = article[..start].rfind("<p>")
+                .map(|p| html::strip_tags(&article[p..start]).to_ascii_lowercase())
+                .unwrap_or_default();
+            (html::strip_tags(&captures[1]), intro.contains("example"))
+        })
+        .collect()
+}
+
+fn last_answer(article: &str) -> Option<String> {
+    let regex = Regex::new(r"(?s)<code><em>(.*?)</em></code>|<em><code>(.*?)</code></em>").unwrap();
+    regex.captures_iter(article)
+        .last()
+        .and_then(|captures| captures.get(1).or(captures.get(2)))
+        .map(|answer| html::strip_tags(answer.as_str()))
+}
+
+pub fn fixture_dir(day: u8) -> Result<PathBuf, Error> {
+    let mut path = env::current_dir()?;
+    path.push(FIXTURE_DIR);
+    path.push(format!("day{day}"));
+    Ok(path)
+}
+
+pub fn write_fixtures(day: u8, examples: &[Example]) -> Result<PathBuf, Error> {
+    let dir = fixture_dir(day)?;
+    write_fixtures_in(&dir, examples)?;
+    Ok(dir)
+}
+
+fn write_fixtures_in(dir: &Path, examples: &[Example]) -> Result<(), Error> {
+    fs::create_dir_all(dir)?;
+    clear_fixtures(dir)?;
+
+    let mut answers = String::new();
+    for (idx, example) in examples.iter().enumerate() {
+        let num = idx + 1;
+        fs::write(dir.join(format!("example{num}.txt")), example.input.as_str())?;
+        for (part, answer) in example.answers.iter().enumerate() {
+            if let Some(answer) = answer {
+                answers.push_str(format!("{num} part{} {answer}\n", part + 1).as_str());
+            }
+        }
+    }
+    fs::write(dir.join(ANSWERS_FILE), answers)?;
+
+    Ok(())
+}
+
+// A re-extract can find fewer examples than last time, so drop the old ones
+// rather than leaving extra exampleN.txt files for read_fixtures to pick up.
+fn clear_fixtures(dir: &Path) -> Result<(), Error> {
+    let fixture = Regex::new(r"^example\d+\.txt$").unwrap();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if fixture.is_match(entry.file_name().to_string_lossy().as_ref()) {
+            fs::remove_file(entry.path())?;
+        }
+    }
+    Ok(())
+}
+
+pub fn read_fixtures(day: u8) -> Result<Vec<Example>, Error> {
+    read_fixtures_in(&fixture_dir(day)?)
+        .map_err(|e| anyhow!("Day {day}: {e}"))
+}
+
+fn read_fixtures_in(dir: &Path) -> Result<Vec<Example>, Error> {
+    let mut examples = Vec::new();
+    loop {
+        let path = dir.join(format!("example{}.txt", examples.len() + 1));
+        if !path.exists() {
+            break;
+        }
+        examples.push(Example::new(fs::read_to_string(path)?));
+    }
+
+    if examples.is_empty() {
+        return Err(anyhow!("no example fixtures in {}", dir.display()));
+    }
+
+    let answers = fs::read_to_string(dir.join(ANSWERS_FILE)).unwrap_or_default();
+    for (line_num, line) in answers.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+        let mut fields = line.splitn(3, ' ');
+        let parsed = fields.next().and_then(|num| num.parse::<usize>().ok())
+            .zip(fields.next().and_then(|part| part.strip_prefix("part")?.parse::<usize>().ok()))
+            .zip(fields.next());
+        match parsed {
+            Some(((num, part @ 1..=2), answer)) if num >= 1 && num <= examples.len() => {
+                examples[num - 1].answers[part - 1] = Some(answer.to_string());
+            },
+            _ => return Err(anyhow!("{ANSWERS_FILE}:{}: invalid answer line: {line}", line_num + 1)),
+        }
+    }
+
+    Ok(examples)
+}
+
+pub fn read_example(day: u8, num: usize) -> Result<Example, Error> {
+    if num == 0 {
+        return Err(anyhow!("Day {day}: examples are numbered from 1"));
+    }
+    read_fixtures(day)?
+        .into_iter()
+        .nth(num - 1)
+        .ok_or_else(|| anyhow!("Day {day}: no example{num} fixture"))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const HTML: &str = r#"<main>
+<article class="day-desc"><h2>--- Day 1: Historian Hysteria ---</h2>
+<p>For example:</p>
+<pre><code>3   4
+4   3
+</code></pre>
+<p>Then, pair them up:</p>
+<pre><code>1 &lt; 3
+</code></pre>
+<p>In the example above, the total distance is <code><em>11</em></code>.</p>
+</article>
+<p>Your puzzle answer was <code>2192892</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<p>Here are the same example lists again:</p>
+<pre><code>3   4
+4   3
+</code></pre>
+<p>So, for these example lists, the similarity score at the end of this process is <code><em>31</em></code>.</p>
+</article>
+</main>"#;
+
+    #[test]
+    fn extract_examples() {
+        let examples = extract(HTML);
+        assert_eq!(examples, vec![Example {
+            input: "3   4\n4   3\n".to_string(),
+            answers: [Some("11".to_string()), Some("31".to_string())],
+        }]);
+        assert_eq!(examples[0].answer(2), Some("31"));
+        assert_eq!(examples[0].answer(0), None);
+        assert_eq!(examples[0].answer(3), None);
+        assert!(read_example(1, 0).is_err());
+    }
+
+    #[test]
+    fn rewrite_fixtures() {
+        let dir = env::temp_dir().join(format!("aoc-fixtures-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let example = |input: &str, answer: &str| Example { input: input.to_string(), answers: [Some(answer.to_string()), None] };
+
+        write_fixtures_in(&dir, &[example("1\n", "1"), example("2\n", "2"), example("3\n", "3")]).expect("Write");
+        write_fixtures_in(&dir, &[example("4\n", "4")]).expect("Rewrite");
+        assert_eq!(read_fixtures_in(&dir).expect("Read"), vec![example("4\n", "4")]);
+        assert!(!dir.join("example2.txt").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+}
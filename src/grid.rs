@@ -1,46 +1,213 @@
+use std::collections::{HashMap, VecDeque};
 use std::fmt::{Display, Formatter};
-use std::ops::{Add, AddAssign, Mul, Sub};
+use std::iter;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
 use std::str::FromStr;
-use anyhow::anyhow;
+use anyhow::{anyhow, Error};
 
 #[derive(Clone, Debug, Default, Hash, Eq, PartialEq)]
 pub struct Grid<T> {
     pub bounds: Position,
-    pub grid: Vec<Vec<T>>,
+    width: usize,
+    cells: Vec<T>,
+    wrapping: bool,
 }
 
 impl <T> Grid<T> {
     pub fn new() -> Grid<T> {
-        Grid { bounds: Position::new(0, 0), grid: vec![] }
+        Grid { bounds: Position::new(0, 0), width: 0, cells: vec![], wrapping: false }
     }
 
-    pub fn init(&mut self, input: &Vec<String>) where T: From<char> {
-        for line in input.iter() {
-            self.grid.push(line.chars().map(|c| c.into()).collect());
+    pub fn filled(width: usize, height: usize, value: T) -> Grid<T> where T: Clone {
+        let bounds = Position::new(width.saturating_sub(1), height.saturating_sub(1));
+        Grid { bounds, width, cells: vec![value; width * height], wrapping: false }
+    }
+
+    // In wrapping mode the grid is a torus: steps, neighbours, moves, point
+    // lookups and distances all wrap around the edges.
+    pub fn wrapping(mut self, wrapping: bool) -> Grid<T> {
+        self.wrapping = wrapping;
+        self
+    }
+
+    pub fn is_wrapping(&self) -> bool {
+        self.wrapping
+    }
+
+    pub fn init<'a>(&mut self, input: impl IntoIterator<Item = &'a str>) -> Result<(), Error>
+    where T: TryFrom<char>, T::Error: Display {
+        *self = Self::parse(input)?;
+        Ok(())
+    }
+
+    pub fn parse<'a>(input: impl IntoIterator<Item = &'a str>) -> Result<Self, Error>
+    where T: TryFrom<char>, T::Error: Display {
+        Self::parse_with(input, T::try_from)
+    }
+
+    pub fn parse_with<'a, E: Display>(
+        input: impl IntoIterator<Item = &'a str>,
+        mut cell: impl FnMut(char) -> Result<T, E>,
+    ) -> Result<Self, Error> {
+        Self::parse_at(input, |_, c| cell(c))
+    }
+
+    fn parse_at<'a, E: Display>(
+        input: impl IntoIterator<Item = &'a str>,
+        mut cell: impl FnMut(Position, char) -> Result<T, E>,
+    ) -> Result<Self, Error> {
+        let mut grid = Self::new();
+        for (y, line) in input.into_iter().enumerate() {
+            let len = grid.cells.len();
+            for (x, c) in line.chars().enumerate() {
+                let value = cell(Position::new(x, y), c).map_err(|e| anyhow!("line {}, column {}: unknown grid character {c:?}: {e}", y + 1, x + 1))?;
+                grid.cells.push(value);
+            }
+            let width = grid.cells.len() - len;
+            if y == 0 {
+                grid.width = width;
+            } else if width != grid.width {
+                return Err(anyhow!("line {}: expected {} columns, found {width}", y + 1, grid.width));
+            }
+        }
+        if grid.cells.is_empty() {
+            return Err(anyhow!("Grid input is empty"));
         }
-        self.bounds = Position::new(self.grid[0].len() - 1, self.grid.len() - 1);
+        grid.bounds = Position::new(grid.width - 1, grid.height() - 1);
+        Ok(grid)
+    }
+
+    pub fn parse_markers<'a>(input: impl IntoIterator<Item = &'a str>, mut markers: Markers, fill: T)
+        -> Result<(Self, Markers), Error>
+    where T: TryFrom<char> + Clone, T::Error: Display {
+        let mut found = Vec::new();
+        let grid = Self::parse_at(input, |pos, c| match markers.is_marker(c) {
+            true => {
+                found.push(Marker { c, pos });
+                Ok(fill.clone())
+            },
+            false => T::try_from(c),
+        })?;
+        markers.found = found;
+        markers.check()?;
+        Ok((grid, markers))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.len().checked_div(self.width).unwrap_or(0)
+    }
+
+    pub fn index_of(&self, pos: Position) -> Option<usize> {
+        (pos.x < self.width && pos.y < self.height()).then(|| pos.y * self.width + pos.x)
+    }
+
+    pub fn position_of(&self, index: usize) -> Option<Position> {
+        (index < self.cells.len()).then(|| Position::new(index % self.width, index / self.width))
     }
 
     pub fn get(&self, pos: Position) -> Option<&T> {
-        self.grid.get(pos.y).and_then(|row| row.get(pos.x))
+        self.index_of(pos).map(|i| &self.cells[i])
     }
 
     pub fn get_dir(&self, pos: Position, dir: &Direction) -> Option<&T> {
-        if (pos.x == 0 && *dir == Direction::Left)
-            || (pos.x == self.bounds.x && *dir == Direction::Right)
-            || (pos.y == 0 && *dir == Direction::Up)
-            || (pos.y == self.bounds.y && *dir == Direction::Down) {
-            return None;
+        self.step(pos, dir).and_then(|pos| self.get(pos))
+    }
+
+    pub fn step(&self, pos: Position, dir: &Direction) -> Option<Position> {
+        self.step_by(pos, dir.to_vector())
+    }
+
+    pub fn step_by(&self, pos: Position, vec: Vector) -> Option<Position> {
+        if self.wrapping {
+            return self.wrap(pos.to_point() + vec);
+        }
+        pos.checked_add(&vec).filter(|pos| self.in_bounds(*pos))
+    }
+
+    pub fn wrap(&self, point: Point) -> Option<Position> {
+        let (width, height) = (self.width as isize, self.height() as isize);
+        (width > 0 && height > 0)
+            .then(|| Position::new(point.x.rem_euclid(width) as usize, point.y.rem_euclid(height) as usize))
+    }
+
+    pub fn distance(&self, a: Position, b: Position) -> usize {
+        let (dx, dy) = (a.x.abs_diff(b.x), a.y.abs_diff(b.y));
+        match self.wrapping {
+            true => dx.min(self.width - dx) + dy.min(self.height() - dy),
+            false => dx + dy,
+        }
+    }
+
+    pub fn neighbours4(&self, pos: Position) -> impl Iterator<Item = Position> + '_ {
+        self.neighbours_in(pos, &Direction::ALL)
+    }
+
+    pub fn neighbours8(&self, pos: Position) -> impl Iterator<Item = Position> + '_ {
+        self.neighbours_in(pos, &Direction::EIGHT)
+    }
+
+    pub fn diagonal_neighbours(&self, pos: Position) -> impl Iterator<Item = Position> + '_ {
+        self.neighbours_in(pos, &Direction::DIAGONALS)
+    }
+
+    // Moves that leave the grid are dropped, unlike Move::grid_move which only
+    // guards against going below zero. When wrapping, the move is made from a
+    // copy of the position shifted by whole grids to the middle of the isize
+    // range and then wrapped back, so even a velocity times 100 stays in range.
+    pub fn moves(&self, state: State, mv: &impl Move) -> Vec<State> {
+        if self.wrapping {
+            let (width, height) = (self.width, self.height());
+            if width == 0 || height == 0 {
+                return vec![];
+            }
+            let middle = isize::MAX as usize / 2;
+            let shifted = Position::new(middle / width * width + state.pos.x, middle / height * height + state.pos.y);
+            return mv.grid_move(State::new(shifted, state.vec)).into_iter()
+                .filter_map(|moved| self.wrap(moved.pos.to_point()).map(|pos| State::new(pos, moved.vec)))
+                .collect();
         }
-        self.get(pos + *dir)
+        mv.grid_move(state).into_iter().filter(|state| self.in_bounds(state.pos)).collect()
+    }
+
+    fn neighbours_in<'a>(&'a self, pos: Position, dirs: &'a [Direction]) -> impl Iterator<Item = Position> + 'a {
+        dirs.iter().filter_map(move |dir| self.step(pos, dir))
+    }
+
+    pub fn get_point(&self, point: Point) -> Option<&T> {
+        match self.wrapping {
+            true => self.wrap(point),
+            false => point.to_position(),
+        }.and_then(|pos| self.get(pos))
     }
 
     pub fn get_mut(&mut self, pos: Position) -> Option<&mut T> {
-        self.grid.get_mut(pos.y).and_then(|row| row.get_mut(pos.x))
+        self.index_of(pos).map(|i| &mut self.cells[i])
     }
 
     pub fn set(&mut self, pos: Position, value: T) {
-        self.grid[pos.y][pos.x] = value;
+        let index = self.index_of(pos).unwrap_or_else(|| panic!("Position {pos} out of bounds"));
+        self.cells[index] = value;
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        (y < self.height()).then(|| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> Option<&mut [T]> {
+        let width = self.width;
+        (y < self.height()).then(|| &mut self.cells[y * width..(y + 1) * width])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn cells(&self) -> &[T] {
+        &self.cells
     }
 
     pub fn enumerate(&self) -> GridIterator<T> {
@@ -48,14 +215,160 @@ impl <T> Grid<T> {
     }
 
     pub fn in_bounds(&self, pos: Position) -> bool {
-        pos.x <= self.bounds.x && pos.y <= self.bounds.y
+        !self.cells.is_empty() && pos.x <= self.bounds.x && pos.y <= self.bounds.y
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.ray(Position::new(x, 0), Direction::Down).map(|(_, value)| value)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    // Top-left to bottom-right, starting along the top row then down the left column.
+    pub fn diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        self.lines(Direction::DownRight).map(|line| line.map(|(_, value)| value))
+    }
+
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        self.lines(Direction::DownLeft).map(|line| line.map(|(_, value)| value))
+    }
+
+    // Walks from start in a straight line until the edge, never wrapping.
+    pub fn ray(&self, start: Position, dir: Direction) -> impl Iterator<Item = (Position, &T)> {
+        let vec = dir.to_vector();
+        iter::successors(self.index_of(start).map(|_| start), move |pos| {
+            pos.checked_add(&vec).filter(|next| self.index_of(*next).is_some())
+        })
+        .map(|pos| (pos, &self.cells[self.index_of(pos).unwrap()]))
+    }
+
+    // Every maximal line through the grid in one direction, one ray per cell
+    // that has no predecessor in that direction.
+    pub fn lines(&self, dir: Direction) -> impl Iterator<Item = impl Iterator<Item = (Position, &T)>> {
+        let vec = dir.to_vector();
+        (0..self.cells.len())
+            .filter_map(|i| self.position_of(i))
+            .filter(move |pos| pos.checked_sub(&vec).and_then(|prev| self.index_of(prev)).is_none())
+            .map(move |start| self.ray(start, dir))
+    }
+
+    pub fn view(&self, origin: Position, width: usize, height: usize) -> Option<SubGrid<'_, T>> {
+        (origin.x + width <= self.width && origin.y + height <= self.height())
+            .then_some(SubGrid { grid: self, origin, width, height })
+    }
+
+    pub fn transpose(&self) -> Grid<T> where T: Clone {
+        self.remap(self.height(), self.width, |pos| Position::new(pos.y, pos.x))
+    }
+
+    pub fn rotate_clockwise(&self) -> Grid<T> where T: Clone {
+        let height = self.height();
+        self.remap(height, self.width, |pos| Position::new(pos.y, height - 1 - pos.x))
+    }
+
+    pub fn rotate_180(&self) -> Grid<T> where T: Clone {
+        let (width, height) = (self.width, self.height());
+        self.remap(width, height, |pos| Position::new(width - 1 - pos.x, height - 1 - pos.y))
+    }
+
+    pub fn rotate_counter_clockwise(&self) -> Grid<T> where T: Clone {
+        let width = self.width;
+        self.remap(self.height(), width, |pos| Position::new(width - 1 - pos.y, pos.x))
+    }
+
+    // Mirrors left to right.
+    pub fn flip_horizontal(&self) -> Grid<T> where T: Clone {
+        let width = self.width;
+        self.remap(width, self.height(), |pos| Position::new(width - 1 - pos.x, pos.y))
+    }
+
+    // Mirrors top to bottom.
+    pub fn flip_vertical(&self) -> Grid<T> where T: Clone {
+        let height = self.height();
+        self.remap(self.width, height, |pos| Position::new(pos.x, height - 1 - pos.y))
+    }
+
+    // Builds a width x height grid where each cell is copied from source(pos).
+    fn remap(&self, width: usize, height: usize, source: impl Fn(Position) -> Position) -> Grid<T> where T: Clone {
+        let cells = (0..width * height)
+            .map(|i| self.get(source(Position::new(i % width, i / width))).unwrap().clone())
+            .collect();
+        let bounds = Position::new(width.saturating_sub(1), height.saturating_sub(1));
+        Grid { bounds, width, cells, wrapping: self.wrapping }
+    }
+
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct SubGrid<'a, T> {
+    grid: &'a Grid<T>,
+    origin: Position,
+    width: usize,
+    height: usize,
+}
+
+impl<'a, T> SubGrid<'a, T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn origin(&self) -> Position {
+        self.origin
+    }
+
+    pub fn get(&self, pos: Position) -> Option<&'a T> {
+        (pos.x < self.width && pos.y < self.height)
+            .then(|| self.grid.get(Position::new(self.origin.x + pos.x, self.origin.y + pos.y)))
+            .flatten()
+    }
+
+    pub fn row(&self, y: usize) -> Option<&'a [T]> {
+        (y < self.height)
+            .then(|| self.grid.row(self.origin.y + y))
+            .flatten()
+            .map(|row| &row[self.origin.x..self.origin.x + self.width])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &'a [T]> + '_ {
+        (0..self.height).filter_map(|y| self.row(y))
+    }
+
+    pub fn to_grid(&self) -> Grid<T> where T: Clone {
+        let cells = self.rows().flat_map(|row| row.iter().cloned()).collect();
+        let bounds = Position::new(self.width.saturating_sub(1), self.height.saturating_sub(1));
+        Grid { bounds, width: self.width, cells, wrapping: false }
+    }
+}
+
+impl<T> Display for SubGrid<'_, T> where T: Display {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            for col in row.iter() {
+                write!(f, "{}", col)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
     }
+}
+
+impl<T> FromStr for Grid<T> where T: TryFrom<char>, T::Error: Display {
+    type Err = Error;
 
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s.lines())
+    }
 }
 
 impl<T> Display for Grid<T> where T: Display {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        for row in self.grid.iter() {
+        for row in self.rows() {
             for col in row.iter() {
                 write!(f, "{}", col)?;
             }
@@ -65,14 +378,91 @@ impl<T> Display for Grid<T> where T: Display {
     }
 }
 
+// Shared by the dense Grid and SparseGrid, addressed by signed Point so sparse
+// grids can grow into negative coordinates. Only cell, area, neighbour and
+// occupied need implementing, the rest builds on those.
+pub trait GridLike<T> {
+    fn cell(&self, point: Point) -> Option<&T>;
+
+    // Inclusive (min, max) corners, None for an empty grid.
+    fn area(&self) -> Option<(Point, Point)>;
+
+    // Every point holding a value, in reading order.
+    fn occupied(&self) -> Vec<Point>;
+
+    fn neighbour(&self, point: Point, dir: Direction) -> Option<Point> {
+        let next = point + dir;
+        self.cell(next).map(|_| next)
+    }
+
+    fn neighbours(&self, point: Point, dirs: &[Direction]) -> Vec<Point> {
+        dirs.iter().filter_map(|dir| self.neighbour(point, *dir)).collect()
+    }
+
+    fn find(&self, pred: impl Fn(&T) -> bool) -> Option<Point> {
+        self.occupied().into_iter().find(|point| self.cell(*point).is_some_and(&pred))
+    }
+
+    fn find_all(&self, pred: impl Fn(&T) -> bool) -> Vec<Point> {
+        self.occupied().into_iter().filter(|point| self.cell(*point).is_some_and(&pred)).collect()
+    }
+
+    fn render(&self, draw: impl Fn(Option<&T>) -> char) -> String {
+        let mut out = String::new();
+        if let Some((min, max)) = self.area() {
+            for y in min.y..=max.y {
+                out.extend((min.x..=max.x).map(|x| draw(self.cell(Point::new(x, y)))));
+                out.push('\n');
+            }
+        }
+        out
+    }
+
+    // Breadth-first step counts from start over 4-connected passable cells.
+    fn distances(&self, start: Point, passable: impl Fn(&T) -> bool) -> HashMap<Point, usize> {
+        let mut distances = HashMap::from([(start, 0)]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(point) = queue.pop_front() {
+            let steps = distances[&point];
+            for next in self.neighbours(point, &Direction::ALL) {
+                if !distances.contains_key(&next) && self.cell(next).is_some_and(&passable) {
+                    distances.insert(next, steps + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+        distances
+    }
+}
+
+impl<T> GridLike<T> for Grid<T> {
+    fn cell(&self, point: Point) -> Option<&T> {
+        self.get_point(point)
+    }
+
+    fn area(&self) -> Option<(Point, Point)> {
+        (!self.cells.is_empty()).then(|| (Point::default(), self.bounds.to_point()))
+    }
+
+    fn occupied(&self) -> Vec<Point> {
+        (0..self.cells.len()).filter_map(|i| self.position_of(i)).map(|pos| pos.to_point()).collect()
+    }
+
+    fn neighbour(&self, point: Point, dir: Direction) -> Option<Point> {
+        point.to_position()
+            .and_then(|pos| self.step(pos, &dir))
+            .map(|pos| pos.to_point())
+    }
+}
+
 pub struct GridIterator<'a, T> {
     grid: &'a Grid<T>,
-    pos: Position,
+    index: usize,
 }
 
 impl <'a, T> GridIterator<'a, T> {
     pub fn new(grid: &'a Grid<T>) -> GridIterator<'a, T> {
-        GridIterator { grid, pos: Position::new(0, 0) }
+        GridIterator { grid, index: 0 }
     }
 }
 
@@ -80,13 +470,102 @@ impl<'a, T> Iterator for GridIterator<'a, T> where T: Clone {
     type Item = (Position, &'a T);
 
     fn next(&mut self) -> Option<Self::Item> {
-        let pos = self.pos;
-        self.pos.x += 1;
-        if self.pos.x > self.grid.bounds.x {
-            self.pos.x = 0;
-            self.pos.y += 1;
+        let index = self.index;
+        self.index += 1;
+        self.grid.position_of(index).map(|pos| (pos, &self.grid.cells[index]))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Markers {
+    groups: Vec<(String, bool)>,
+    // Every grid cell is checked against the markers, so ASCII markers get a
+    // lookup table and only the rare non-ASCII ones are searched.
+    ascii: [bool; 128],
+    other: String,
+    found: Vec<Marker>,
+}
+
+impl Markers {
+    pub fn new() -> Markers {
+        Markers { groups: vec![], ascii: [false; 128], other: String::new(), found: vec![] }
+    }
+
+    // Exactly one cell in the grid must hold one of these characters.
+    pub fn required(self, chars: &str) -> Markers {
+        self.group(chars, true)
+    }
+
+    pub fn optional(self, chars: &str) -> Markers {
+        self.group(chars, false)
+    }
+
+    fn group(mut self, chars: &str, required: bool) -> Markers {
+        for c in chars.chars() {
+            match c.is_ascii() {
+                true => self.ascii[c as usize] = true,
+                false => self.other.push(c),
+            }
+        }
+        self.groups.push((chars.to_string(), required));
+        self
+    }
+
+    pub fn get(&self, c: char) -> Result<Marker, Error> {
+        self.all(c).next().ok_or_else(|| anyhow!("No {c:?} marker in grid"))
+    }
+
+    pub fn find(&self, chars: &str) -> Result<Marker, Error> {
+        self.found.iter()
+            .find(|marker| chars.contains(marker.c))
+            .copied()
+            .ok_or_else(|| anyhow!("No {chars:?} marker in grid"))
+    }
+
+    pub fn all(&self, c: char) -> impl Iterator<Item = Marker> + '_ {
+        self.found.iter().filter(move |marker| marker.c == c).copied()
+    }
+
+    fn is_marker(&self, c: char) -> bool {
+        match c.is_ascii() {
+            true => self.ascii[c as usize],
+            false => self.other.contains(c),
         }
-        self.grid.get(pos).map(|v| (pos, v))
+    }
+
+    fn check(&self) -> Result<(), Error> {
+        for (chars, _) in self.groups.iter().filter(|(_, required)| *required) {
+            let mut found = self.found.iter().filter(|marker| chars.contains(marker.c));
+            match (found.next(), found.next()) {
+                (None, _) => return Err(anyhow!("Missing marker {chars:?}")),
+                (Some(first), Some(second)) => return Err(anyhow!(
+                    "Duplicate marker {chars:?}: {} and {}", first.location(), second.location())),
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for Markers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct Marker {
+    pub c: char,
+    pub pos: Position,
+}
+
+impl Marker {
+    pub fn direction(&self) -> Option<Direction> {
+        Direction::from_arrow(self.c)
+    }
+
+    fn location(&self) -> String {
+        format!("line {}, column {}", self.pos.y + 1, self.pos.x + 1)
     }
 }
 
@@ -107,6 +586,9 @@ impl State {
 }
 
 
+// Every coordinate type here uses the same convention: x is the column and grows
+// to the right, y is the row and grows downwards. Tuples convert as (x, y) and
+// Display prints (x, y).
 #[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq)]
 pub struct Position {
     pub x: usize,
@@ -130,11 +612,15 @@ impl Position {
             .map(|(x, y)| Position::new(x, y))
     }
 
+    pub fn to_point(self) -> Point {
+        self.into()
+    }
+
 }
 
 impl Display for Position {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {})", self.y, self.x)
+        write!(f, "({}, {})", self.x, self.y)
     }
 }
 
@@ -205,13 +691,13 @@ impl Vector {
 
 impl From<(isize, isize)> for Vector {
     fn from(vec: (isize, isize)) -> Self {
-        Vector::new(vec.1, vec.0)
+        Vector::new(vec.0, vec.1)
     }
 }
 
 impl From<Direction> for Vector {
     fn from(value: Direction) -> Self {
-        value.to_tuple().into()
+        value.to_vector()
     }
 }
 
@@ -238,6 +724,29 @@ impl AddAssign for Vector {
     }
 }
 
+impl Sub for Vector {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::Output { x: self.x - rhs.x, y: self.y - rhs.y }
+    }
+}
+
+impl SubAssign for Vector {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+impl Neg for Vector {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::Output { x: -self.x, y: -self.y }
+    }
+}
+
 impl Move for Vector {
     fn grid_move(&self, state: State) -> Vec<State> {
         state.pos.checked_add(self)
@@ -248,7 +757,7 @@ impl Move for Vector {
 
 impl ToVector for Vector {
     fn to_tuple(&self) -> (isize, isize) {
-        (self.y, self.x)
+        (self.x, self.y)
     }
 
     fn to_vector(&self) -> Vector {
@@ -258,49 +767,185 @@ impl ToVector for Vector {
 
 impl Display for Vector {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {})", self.y, self.x)
+        write!(f, "({}, {})", self.x, self.y)
     }
 }
 
 
-#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Point {
+    pub fn new(x: isize, y: isize) -> Point {
+        Point { x, y }
+    }
+
+    pub fn to_position(self) -> Option<Position> {
+        usize::try_from(self.x).ok()
+            .zip(usize::try_from(self.y).ok())
+            .map(|(x, y)| Position::new(x, y))
+    }
+
+    pub fn manhattan(&self, other: Point) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl From<(isize, isize)> for Point {
+    fn from(point: (isize, isize)) -> Self {
+        Self::new(point.0, point.1)
+    }
+}
+
+impl From<Position> for Point {
+    fn from(pos: Position) -> Self {
+        Self::new(pos.x as isize, pos.y as isize)
+    }
+}
+
+impl TryFrom<Point> for Position {
+    type Error = Error;
+
+    fn try_from(point: Point) -> Result<Self, Self::Error> {
+        point.to_position().ok_or_else(|| anyhow!("Point {point} is not a grid position"))
+    }
+}
+
+impl Add<Vector> for Point {
+    type Output = Self;
+
+    fn add(self, rhs: Vector) -> Self::Output {
+        Self::Output { x: self.x + rhs.x, y: self.y + rhs.y }
+    }
+}
+
+impl AddAssign<Vector> for Point {
+    fn add_assign(&mut self, rhs: Vector) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl Sub<Vector> for Point {
+    type Output = Self;
+
+    fn sub(self, rhs: Vector) -> Self::Output {
+        Self::Output { x: self.x - rhs.x, y: self.y - rhs.y }
+    }
+}
+
+impl SubAssign<Vector> for Point {
+    fn sub_assign(&mut self, rhs: Vector) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+impl Add<Direction> for Point {
+    type Output = Self;
+
+    fn add(self, rhs: Direction) -> Self::Output {
+        self + rhs.to_vector()
+    }
+}
+
+impl AddAssign<Direction> for Point {
+    fn add_assign(&mut self, rhs: Direction) {
+        *self += rhs.to_vector();
+    }
+}
+
+impl Sub for Point {
+    type Output = Vector;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::Output { x: self.x - rhs.x, y: self.y - rhs.y }
+    }
+}
+
+
+// Variants run clockwise from Up, so the discriminant doubles as the index
+// into Direction::EIGHT and turning is index arithmetic.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
 pub enum Direction {
     Up,
+    UpRight,
+    Right,
+    DownRight,
     Down,
+    DownLeft,
     Left,
-    Right,
-    North,
-    South,
-    East,
-    West,
+    UpLeft,
 }
 
 impl Direction {
     pub const ALL: [Direction; 4] = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+    pub const DIAGONALS: [Direction; 4] = [Direction::UpLeft, Direction::UpRight, Direction::DownLeft, Direction::DownRight];
+    pub const EIGHT: [Direction; 8] = [
+        Direction::Up, Direction::UpRight, Direction::Right, Direction::DownRight,
+        Direction::Down, Direction::DownLeft, Direction::Left, Direction::UpLeft,
+    ];
+
+    pub const NORTH: Direction = Direction::Up;
+    pub const SOUTH: Direction = Direction::Down;
+    pub const EAST: Direction = Direction::Right;
+    pub const WEST: Direction = Direction::Left;
+
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    pub fn from_index(index: usize) -> Direction {
+        Direction::EIGHT[index % 8]
+    }
+
+    // One step is 45 degrees, positive steps turn clockwise.
+    pub fn turn(&self, steps: isize) -> Direction {
+        Direction::from_index((self.index() as isize + steps).rem_euclid(8) as usize)
+    }
 
     pub fn flip(&self) -> Direction {
-        match self {
-            Direction::Up => Direction::Down,
-            Direction::Down => Direction::Up,
-            Direction::Left => Direction::Right,
-            Direction::Right => Direction::Left,
-            Direction::North => Direction::South,
-            Direction::South => Direction::North,
-            Direction::East => Direction::West,
-            Direction::West => Direction::East,
-        }
+        self.turn(4)
     }
 
     pub fn clockwise(&self) -> Direction {
+        self.turn(2)
+    }
+
+    pub fn counter_clockwise(&self) -> Direction {
+        self.turn(-2)
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        self.index() % 2 == 1
+    }
+
+    pub fn from_arrow(c: char) -> Option<Direction> {
+        match c {
+            '^' => Some(Direction::Up),
+            'v' => Some(Direction::Down),
+            '<' => Some(Direction::Left),
+            '>' => Some(Direction::Right),
+            _ => None,
+        }
+    }
+
+    pub fn to_arrow(self) -> Option<char> {
         match self {
-            Direction::Up => Direction::Right,
-            Direction::Down => Direction::Left,
-            Direction::Left => Direction::Up,
-            Direction::Right => Direction::Down,
-            Direction::North => Direction::East,
-            Direction::South => Direction::West,
-            Direction::East => Direction::South,
-            Direction::West => Direction::North,
+            Direction::Up => Some('^'),
+            Direction::Down => Some('v'),
+            Direction::Left => Some('<'),
+            Direction::Right => Some('>'),
+            _ => None,
         }
     }
 
@@ -309,19 +954,30 @@ impl Direction {
     }
 }
 
+impl TryFrom<char> for Direction {
+    type Error = anyhow::Error;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match Direction::from_arrow(c) {
+            Some(dir) => Ok(dir),
+            None => c.to_string().parse(),
+        }
+    }
+}
+
 impl FromStr for Direction {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_ascii_lowercase().as_str() {
-            "u" | "up" => Ok(Direction::Up),
-            "d" | "down" => Ok(Direction::Down),
-            "l" | "left" => Ok(Direction::Left),
-            "r" | "right" => Ok(Direction::Right),
-            "n" | "north" => Ok(Direction::North),
-            "s" | "south" => Ok(Direction::South),
-            "w" | "west" => Ok(Direction::West),
-            "e" | "east" => Ok(Direction::East),
+            "^" | "u" | "up" | "n" | "north" => Ok(Direction::Up),
+            "v" | "d" | "down" | "s" | "south" => Ok(Direction::Down),
+            "<" | "l" | "left" | "w" | "west" => Ok(Direction::Left),
+            ">" | "r" | "right" | "e" | "east" => Ok(Direction::Right),
+            "ul" | "up-left" | "nw" | "northwest" => Ok(Direction::UpLeft),
+            "ur" | "up-right" | "ne" | "northeast" => Ok(Direction::UpRight),
+            "dl" | "down-left" | "sw" | "southwest" => Ok(Direction::DownLeft),
+            "dr" | "down-right" | "se" | "southeast" => Ok(Direction::DownRight),
             _ => Err(anyhow!("Invalid direction: {}", s)),
         }
     }
@@ -334,10 +990,10 @@ impl Display for Direction {
             Direction::Down => "Down",
             Direction::Left => "Left",
             Direction::Right => "Right",
-            Direction::North => "North",
-            Direction::South => "South",
-            Direction::East => "East",
-            Direction::West => "West",
+            Direction::UpLeft => "UpLeft",
+            Direction::UpRight => "UpRight",
+            Direction::DownLeft => "DownLeft",
+            Direction::DownRight => "DownRight",
         };
         write!(f, "{}", c)
     }
@@ -345,20 +1001,19 @@ impl Display for Direction {
 
 impl ToVector for Direction {
     fn to_tuple(&self) -> (isize, isize) {
-        match self {
-            Direction::Up | Direction::North => (-1, 0),
-            Direction::Down | Direction::South => (1, 0),
-            Direction::Left | Direction::West => (0, -1),
-            Direction::Right | Direction::East => (0, 1),
-        }
+        self.to_vector().to_tuple()
     }
 
     fn to_vector(&self) -> Vector {
         match self {
-            Direction::Up | Direction::North => Vector::new(0, -1),
-            Direction::Down | Direction::South => Vector::new(0, 1),
-            Direction::Left | Direction::West => Vector::new(-1, 0),
-            Direction::Right | Direction::East => Vector::new(1, 0),
+            Direction::Up => Vector::new(0, -1),
+            Direction::Down => Vector::new(0, 1),
+            Direction::Left => Vector::new(-1, 0),
+            Direction::Right => Vector::new(1, 0),
+            Direction::UpLeft => Vector::new(-1, -1),
+            Direction::UpRight => Vector::new(1, -1),
+            Direction::DownLeft => Vector::new(-1, 1),
+            Direction::DownRight => Vector::new(1, 1),
         }
     }
 }
@@ -375,7 +1030,244 @@ pub trait ToVector {
 
 
 impl Move for Direction {
-    fn grid_move(&self, _state: State) -> Vec<State> {
-        todo!()
+    fn grid_move(&self, state: State) -> Vec<State> {
+        self.to_vector().grid_move(state)
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn flat_storage() {
+        let mut grid: Grid<char> = Grid::parse(["ab", "cd", "ef"]).expect("Parse");
+        assert_eq!((grid.width(), grid.height()), (2, 3));
+        assert_eq!(grid.bounds, Position::new(1, 2));
+        assert_eq!(grid.index_of(Position::new(1, 1)), Some(3));
+        assert_eq!(grid.index_of(Position::new(2, 0)), None);
+        assert_eq!(grid.position_of(4), Some(Position::new(0, 2)));
+        assert_eq!(grid.position_of(6), None);
+        assert_eq!(grid.row(1), Some(&['c', 'd'][..]));
+        assert_eq!(grid.get(Position::new(1, 2)), Some(&'f'));
+
+        grid.set(Position::new(0, 1), 'x');
+        grid.row_mut(2).unwrap()[1] = 'y';
+        assert_eq!(grid.to_string(), "ab\nxd\ney\n");
+        assert_eq!(grid.enumerate().map(|(_, c)| *c).collect::<String>(), "abxdey");
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum Tile {
+        Open,
+        Wall,
+    }
+
+    impl TryFrom<char> for Tile {
+        type Error = Error;
+
+        fn try_from(c: char) -> Result<Self, Self::Error> {
+            match c {
+                '.' => Ok(Tile::Open),
+                '#' => Ok(Tile::Wall),
+                _ => Err(anyhow!("expected '.' or '#'")),
+            }
+        }
+    }
+
+    impl Display for Tile {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{}", if *self == Tile::Wall { '#' } else { '.' })
+        }
+    }
+
+    #[test]
+    fn parse() {
+        let text = "..#\n#..\n";
+        let grid: Grid<Tile> = text.parse().expect("Parse");
+        assert_eq!(grid.get(Position::new(2, 0)), Some(&Tile::Wall));
+        assert_eq!(grid.to_string(), text);
+
+        let err = "..#\n#x.\n".parse::<Grid<Tile>>().unwrap_err();
+        assert_eq!(err.to_string(), "line 2, column 2: unknown grid character 'x': expected '.' or '#'");
+        let err = "..#\n#.\n".parse::<Grid<Tile>>().unwrap_err();
+        assert_eq!(err.to_string(), "line 2: expected 3 columns, found 2");
+        assert!("".parse::<Grid<Tile>>().is_err());
+
+        let digits = Grid::parse_with(["12", "34"], |c| c.to_digit(10).ok_or("not a digit")).expect("Parse");
+        assert_eq!(digits.row(1), Some(&[3, 4][..]));
+    }
+
+    #[test]
+    fn markers() {
+        let lines = ["S.#", ".>.", "#.E"];
+        let markers = Markers::new().required("S").required("E").required("^v<>");
+        let (grid, markers) = Grid::<Tile>::parse_markers(lines, markers, Tile::Open).expect("Parse");
+        assert_eq!(grid.to_string(), "..#\n...\n#..\n");
+        assert_eq!(markers.get('S').unwrap().pos, Position::new(0, 0));
+        assert_eq!(markers.get('E').unwrap().pos, Position::new(2, 2));
+        assert_eq!(markers.find("^v<>").unwrap().direction(), Some(Direction::Right));
+
+        let err = Grid::<Tile>::parse_markers(lines, Markers::new().required("S").required("E"), Tile::Open).unwrap_err();
+        assert_eq!(err.to_string(), "line 2, column 2: unknown grid character '>': expected '.' or '#'");
+        let err = Grid::<Tile>::parse_markers(["S.S"], Markers::new().required("S"), Tile::Open).unwrap_err();
+        assert_eq!(err.to_string(), "Duplicate marker \"S\": line 1, column 1 and line 1, column 3");
+        let err = Grid::<Tile>::parse_markers(["..."], Markers::new().required("S"), Tile::Open).unwrap_err();
+        assert_eq!(err.to_string(), "Missing marker \"S\"");
+
+        let (_, boxes) = Grid::<Tile>::parse_markers(["O.O"], Markers::new().optional("O"), Tile::Open).expect("Parse");
+        assert_eq!(boxes.all('O').count(), 2);
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid: Grid<char> = "abc\ndef\nghi\n".parse().expect("Parse");
+        let values = |positions: Vec<Position>| positions.iter().map(|pos| grid.get(*pos).unwrap()).collect::<String>();
+        assert_eq!(values(grid.neighbours4(Position::new(0, 0)).collect()), "db");
+        assert_eq!(values(grid.neighbours8(Position::new(1, 1)).collect()), "bcfihgda");
+        assert_eq!(values(grid.neighbours8(Position::new(2, 2)).collect()), "fhe");
+        assert_eq!(values(grid.diagonal_neighbours(Position::new(2, 1)).collect()), "bh");
+        assert_eq!(grid.get_dir(Position::new(0, 2), &Direction::UpRight), Some(&'e'));
+        assert_eq!(grid.get_dir(Position::new(0, 2), &Direction::DownRight), None);
+    }
+
+    #[test]
+    fn eight_way_rotation() {
+        for dir in Direction::EIGHT {
+            assert_eq!(dir.clockwise().clockwise(), dir.flip());
+            assert_eq!(dir.clockwise().is_diagonal(), dir.is_diagonal());
+        }
+        assert_eq!(Direction::UpRight.clockwise(), Direction::DownRight);
+        assert_eq!("ne".parse::<Direction>().unwrap(), Direction::UpRight);
+    }
+
+    #[test]
+    fn direction_api() {
+        assert_eq!(Direction::Up.counter_clockwise(), Direction::Left);
+        assert_eq!(Direction::Left.turn(3), Direction::UpRight);
+        assert_eq!(Direction::Up.turn(-9), Direction::UpLeft);
+        for (i, dir) in Direction::EIGHT.iter().enumerate() {
+            assert_eq!(dir.index(), i);
+            assert_eq!(Direction::from_index(i), *dir);
+            assert_eq!(dir.clockwise().counter_clockwise(), *dir);
+        }
+
+        let parsed: Vec<Direction> = "^>v<NsEw".chars().map(|c| c.try_into().unwrap()).collect();
+        assert_eq!(parsed, [Direction::Up, Direction::Right, Direction::Down, Direction::Left,
+            Direction::NORTH, Direction::SOUTH, Direction::EAST, Direction::WEST]);
+        assert_eq!(Direction::Left.to_arrow(), Some('<'));
+        assert!(Direction::try_from('x').is_err());
+    }
+
+    #[test]
+    fn grid_move() {
+        let grid: Grid<char> = "...\n...\n".parse().expect("Parse");
+        let state = State::new(Position::new(0, 1), Vector::new(0, 0));
+        assert_eq!(Direction::Left.grid_move(state), vec![]);
+        assert_eq!(Direction::Right.grid_move(state), vec![State::new(Position::new(1, 1), Vector::new(1, 0))]);
+        assert_eq!(grid.moves(state, &Direction::Down), vec![]);
+        assert_eq!(grid.moves(state, &Direction::UpRight).len(), 1);
+    }
+
+    #[test]
+    fn points() {
+        let a = Point::new(1, 2);
+        let b = a + Vector::new(-3, 1);
+        assert_eq!(b, Point::new(-2, 3));
+        assert_eq!(b - a, Vector::new(-3, 1));
+        assert_eq!(a.manhattan(b), 4);
+        assert_eq!(b.to_position(), None);
+        assert!(Position::try_from(b).is_err());
+        assert_eq!(Position::try_from(a + Direction::Up).unwrap(), Position::new(1, 1));
+        assert_eq!(Position::new(3, 4).to_point(), Point::from((3, 4)));
+
+        let grid: Grid<char> = "ab\ncd\n".parse().expect("Parse");
+        assert_eq!(grid.get_point(Point::new(1, 0)), Some(&'b'));
+        assert_eq!(grid.get_point(Point::new(-1, 0)), None);
+    }
+
+    #[test]
+    fn xy_convention() {
+        assert_eq!(Position::from((3, 4)).to_string(), "(3, 4)");
+        assert_eq!(Vector::from((3, 4)), Vector::new(3, 4));
+        assert_eq!(Vector::new(3, 4).to_string(), "(3, 4)");
+        assert_eq!(Point::from((3, -4)).to_string(), "(3, -4)");
+        assert_eq!(Direction::Right.to_tuple(), (1, 0));
+        assert_eq!(Vector::from(Direction::Up), Vector::new(0, -1));
+    }
+
+    #[test]
+    fn wrapping() {
+        let grid = Grid::filled(11, 7, '.').wrapping(true);
+        let robot = Position::new(2, 4);
+        assert_eq!(grid.step_by(robot, Vector::new(2, -3) * 5), Some(Position::new(1, 3)));
+        assert_eq!(grid.step(Position::new(0, 0), &Direction::UpLeft), Some(Position::new(10, 6)));
+        assert_eq!(grid.neighbours4(Position::new(10, 0)).count(), 4);
+        assert_eq!(grid.get_point(Point::new(-1, -8)), Some(&'.'));
+        assert_eq!(grid.distance(Position::new(0, 0), Position::new(10, 6)), 2);
+
+        let state = State::new(Position::new(0, 3), Vector::default());
+        assert_eq!(grid.moves(state, &Direction::Left), vec![State::new(Position::new(10, 3), Vector::new(-1, 0))]);
+        let robot = State::new(Position::new(2, 4), Vector::default());
+        assert_eq!(grid.moves(robot, &(Vector::new(2, -3) * 100)), vec![State::new(Position::new(4, 5), Vector::new(200, -300))]);
+        assert_eq!(grid.moves(robot, &Vector::new(-1_000_003, 7)), vec![State::new(Position::new(9, 4), Vector::new(-1_000_003, 7))]);
+
+        let bounded = Grid::filled(11, 7, '.');
+        assert_eq!(bounded.step(Position::new(0, 0), &Direction::UpLeft), None);
+        assert_eq!(bounded.distance(Position::new(0, 0), Position::new(10, 6)), 16);
+    }
+
+    #[test]
+    fn grid_like() {
+        let grid: Grid<char> = "S.#\n..#\n#..\n".parse().expect("Parse");
+        let start = grid.find(|c| *c == 'S').expect("Start");
+        assert_eq!(start, Point::new(0, 0));
+        assert_eq!(grid.find_all(|c| *c == '#').len(), 3);
+        assert_eq!(grid.neighbours(start, &Direction::EIGHT), vec![Point::new(1, 0), Point::new(1, 1), Point::new(0, 1)]);
+        assert_eq!(grid.distances(start, |c| *c != '#').get(&Point::new(2, 2)), Some(&4));
+        assert_eq!(grid.render(|c| *c.unwrap()), grid.to_string());
+    }
+
+    #[test]
+    fn transforms() {
+        let grid: Grid<char> = "abc\ndef\n".parse().expect("Parse");
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf\n");
+        assert_eq!(grid.rotate_clockwise().to_string(), "da\neb\nfc\n");
+        assert_eq!(grid.rotate_180().to_string(), "fed\ncba\n");
+        assert_eq!(grid.rotate_counter_clockwise().to_string(), "cf\nbe\nad\n");
+        assert_eq!(grid.flip_horizontal().to_string(), "cba\nfed\n");
+        assert_eq!(grid.flip_vertical().to_string(), "def\nabc\n");
+        assert_eq!(grid.rotate_clockwise().rotate_clockwise(), grid.rotate_180());
+        assert_eq!(grid.rotate_clockwise().bounds, Position::new(1, 2));
+    }
+
+    #[test]
+    fn views_and_slices() {
+        let grid: Grid<char> = "abcd\nefgh\nijkl\n".parse().expect("Parse");
+        let view = grid.view(Position::new(1, 1), 2, 2).expect("View");
+        assert_eq!(view.to_string(), "fg\njk\n");
+        assert_eq!(view.get(Position::new(1, 0)), Some(&'g'));
+        assert_eq!(view.get(Position::new(2, 0)), None);
+        assert_eq!(view.to_grid().rotate_180().to_string(), "kj\ngf\n");
+        assert!(grid.view(Position::new(3, 0), 2, 1).is_none());
+
+        let strings = |lines: Vec<String>| lines.join(" ");
+        assert_eq!(strings(grid.columns().map(|c| c.collect()).collect()), "aei bfj cgk dhl");
+        assert_eq!(strings(grid.diagonals().map(|d| d.collect()).collect()), "afk bgl ch d ej i");
+        assert_eq!(strings(grid.anti_diagonals().map(|d| d.collect()).collect()), "a be cfi dgj hk l");
+    }
+
+    #[test]
+    fn word_search() {
+        let grid: Grid<char> = ["MMMSXXMASM", "MSAMXMSMSA", "AMXSXMAAMM", "MSAMASMSMX", "XMASAMXAMM",
+                                "XXAMMXXAMA", "SMSMSASXSS", "SAXAMASAAA", "MAMMMXMMMM", "MXMXAXMASX"]
+            .join("\n").parse().expect("Parse");
+        let count: usize = Direction::EIGHT.iter()
+            .flat_map(|dir| grid.lines(*dir))
+            .map(|line| line.map(|(_, c)| *c).collect::<String>().matches("XMAS").count())
+            .sum();
+        assert_eq!(count, 18);
+    }
+
+}
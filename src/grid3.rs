@@ -0,0 +1,323 @@
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+use anyhow::{anyhow, Error};
+use crate::sparse::{Coord, SparseGrid};
+
+// Same convention as the 2D types, extended with z: tuples convert as
+// (x, y, z) and Display prints (x, y, z).
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Point3 {
+    pub x: isize,
+    pub y: isize,
+    pub z: isize,
+}
+
+impl Point3 {
+    pub fn new(x: isize, y: isize, z: isize) -> Point3 {
+        Point3 { x, y, z }
+    }
+
+    pub fn manhattan(&self, other: Point3) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+
+    pub fn neighbours6(self) -> impl Iterator<Item = Point3> {
+        Vector3::FACES.into_iter().map(move |vec| self + vec)
+    }
+
+    pub fn neighbours26(self) -> impl Iterator<Item = Point3> {
+        (-1..=1).flat_map(|z| (-1..=1).flat_map(move |y| (-1..=1).map(move |x| Vector3::new(x, y, z))))
+            .filter(|vec| *vec != Vector3::default())
+            .map(move |vec| self + vec)
+    }
+
+    pub fn get(&self, axis: Axis) -> isize {
+        match axis {
+            Axis::X => self.x,
+            Axis::Y => self.y,
+            Axis::Z => self.z,
+        }
+    }
+}
+
+impl Display for Point3 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+impl FromStr for Point3 {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (x, y, z) = parse_triple(s)?;
+        Ok(Point3::new(x, y, z))
+    }
+}
+
+impl Coord for Point3 {
+    fn lower(self, other: Self) -> Self {
+        Point3::new(self.x.min(other.x), self.y.min(other.y), self.z.min(other.z))
+    }
+
+    fn upper(self, other: Self) -> Self {
+        Point3::new(self.x.max(other.x), self.y.max(other.y), self.z.max(other.z))
+    }
+
+    fn on_edge(self, min: Self, max: Self) -> bool {
+        [Axis::X, Axis::Y, Axis::Z].into_iter()
+            .any(|axis| self.get(axis) == min.get(axis) || self.get(axis) == max.get(axis))
+    }
+}
+
+impl From<(isize, isize, isize)> for Point3 {
+    fn from(point: (isize, isize, isize)) -> Self {
+        Point3::new(point.0, point.1, point.2)
+    }
+}
+
+impl Add<Vector3> for Point3 {
+    type Output = Self;
+
+    fn add(self, rhs: Vector3) -> Self::Output {
+        Self::Output { x: self.x + rhs.x, y: self.y + rhs.y, z: self.z + rhs.z }
+    }
+}
+
+impl AddAssign<Vector3> for Point3 {
+    fn add_assign(&mut self, rhs: Vector3) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+        self.z += rhs.z;
+    }
+}
+
+impl Sub<Vector3> for Point3 {
+    type Output = Self;
+
+    fn sub(self, rhs: Vector3) -> Self::Output {
+        Self::Output { x: self.x - rhs.x, y: self.y - rhs.y, z: self.z - rhs.z }
+    }
+}
+
+impl SubAssign<Vector3> for Point3 {
+    fn sub_assign(&mut self, rhs: Vector3) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+        self.z -= rhs.z;
+    }
+}
+
+impl Sub for Point3 {
+    type Output = Vector3;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::Output { x: self.x - rhs.x, y: self.y - rhs.y, z: self.z - rhs.z }
+    }
+}
+
+
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub struct Vector3 {
+    pub x: isize,
+    pub y: isize,
+    pub z: isize,
+}
+
+impl Vector3 {
+    pub const FACES: [Vector3; 6] = [
+        Vector3 { x: 1, y: 0, z: 0 }, Vector3 { x: -1, y: 0, z: 0 },
+        Vector3 { x: 0, y: 1, z: 0 }, Vector3 { x: 0, y: -1, z: 0 },
+        Vector3 { x: 0, y: 0, z: 1 }, Vector3 { x: 0, y: 0, z: -1 },
+    ];
+
+    pub fn new(x: isize, y: isize, z: isize) -> Vector3 {
+        Vector3 { x, y, z }
+    }
+}
+
+impl Display for Vector3 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+impl FromStr for Vector3 {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (x, y, z) = parse_triple(s)?;
+        Ok(Vector3::new(x, y, z))
+    }
+}
+
+impl From<(isize, isize, isize)> for Vector3 {
+    fn from(vec: (isize, isize, isize)) -> Self {
+        Vector3::new(vec.0, vec.1, vec.2)
+    }
+}
+
+impl Add for Vector3 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::Output { x: self.x + rhs.x, y: self.y + rhs.y, z: self.z + rhs.z }
+    }
+}
+
+impl AddAssign for Vector3 {
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+        self.z += rhs.z;
+    }
+}
+
+impl Sub for Vector3 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::Output { x: self.x - rhs.x, y: self.y - rhs.y, z: self.z - rhs.z }
+    }
+}
+
+impl SubAssign for Vector3 {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+        self.z -= rhs.z;
+    }
+}
+
+impl Neg for Vector3 {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::Output { x: -self.x, y: -self.y, z: -self.z }
+    }
+}
+
+impl Mul<i32> for Vector3 {
+    type Output = Self;
+
+    fn mul(self, rhs: i32) -> Self::Output {
+        Self::Output { x: self.x * rhs as isize, y: self.y * rhs as isize, z: self.z * rhs as isize }
+    }
+}
+
+fn parse_triple(s: &str) -> Result<(isize, isize, isize), Error> {
+    let parts: Vec<&str> = s.split(',').map(str::trim).collect();
+    match parts.as_slice() {
+        [x, y, z] => Ok((x.parse()?, y.parse()?, z.parse()?)),
+        _ => Err(anyhow!("Expected x,y,z: {:?}", s)),
+    }
+}
+
+
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+// Sparse so that brick towers and hailstone paths with large or negative
+// coordinates stay cheap. Storage and bounds are SparseGrid's, keyed by Point3.
+pub type Grid3<T> = SparseGrid<T, Point3>;
+
+impl<T> Grid3<T> {
+
+    pub fn in_bounds(&self, point: Point3) -> bool {
+        self.bounds().is_some_and(|(min, max)| {
+            (min.x..=max.x).contains(&point.x) && (min.y..=max.y).contains(&point.y) && (min.z..=max.z).contains(&point.z)
+        })
+    }
+
+    pub fn neighbours6(&self, point: Point3) -> impl Iterator<Item = Point3> + '_ {
+        point.neighbours6().filter(move |next| self.contains(*next))
+    }
+
+    pub fn neighbours26(&self, point: Point3) -> impl Iterator<Item = Point3> + '_ {
+        point.neighbours26().filter(move |next| self.contains(*next))
+    }
+
+    // Renders the plane where `axis` equals `level`. The remaining two axes
+    // become columns and rows in x, y, z order, so a Z slice is the usual
+    // x across, y down view and an X slice puts y across and z down.
+    pub fn render_slice(&self, axis: Axis, level: isize, draw: impl Fn(Option<&T>) -> char) -> String {
+        let Some((min, max)) = self.bounds() else {
+            return String::new();
+        };
+        let (across, down) = match axis {
+            Axis::X => (Axis::Y, Axis::Z),
+            Axis::Y => (Axis::X, Axis::Z),
+            Axis::Z => (Axis::X, Axis::Y),
+        };
+
+        let mut out = String::new();
+        for row in min.get(down)..=max.get(down) {
+            for col in min.get(across)..=max.get(across) {
+                let mut coords = [0; 3];
+                coords[axis_index(axis)] = level;
+                coords[axis_index(across)] = col;
+                coords[axis_index(down)] = row;
+                out.push(draw(self.get(Point3::new(coords[0], coords[1], coords[2]))));
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+}
+
+fn axis_index(axis: Axis) -> usize {
+    match axis {
+        Axis::X => 0,
+        Axis::Y => 1,
+        Axis::Z => 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn points() {
+        let a: Point3 = "1,0,1".parse().expect("Parse");
+        let v: Vector3 = "-2, 1, 3".parse().expect("Parse");
+        assert_eq!(a + v * 2, Point3::new(-3, 2, 7));
+        assert_eq!((a + v) - a, v);
+        assert_eq!(a.manhattan(a + v), 6);
+        assert_eq!(-v, Vector3::new(2, -1, -3));
+        assert!("1,2".parse::<Point3>().is_err());
+
+        assert_eq!(a.neighbours6().count(), 6);
+        assert_eq!(a.neighbours26().count(), 26);
+        assert!(a.neighbours26().all(|n| n != a && (n - a).x.abs() <= 1));
+    }
+
+    #[test]
+    fn grid() {
+        let mut grid = Grid3::new();
+        for point in ["1,0,1", "1,1,1", "1,2,1", "0,0,2", "2,0,2"] {
+            grid.insert(point.parse().unwrap(), 'A');
+        }
+        assert_eq!(grid.bounds(), Some((Point3::new(0, 0, 1), Point3::new(2, 2, 2))));
+        assert!(grid.in_bounds(Point3::new(0, 2, 1)));
+        assert_eq!(grid.neighbours6(Point3::new(1, 1, 1)).count(), 2);
+        assert_eq!(grid.neighbours26(Point3::new(1, 0, 1)).count(), 3);
+
+        let draw = |c: Option<&char>| if c.is_some() { '#' } else { '.' };
+        assert_eq!(grid.render_slice(Axis::Z, 1, draw), ".#.\n.#.\n.#.\n");
+        assert_eq!(grid.render_slice(Axis::Y, 0, draw), ".#.\n#.#\n");
+        assert_eq!(grid.render_slice(Axis::X, 1, draw), "###\n...\n");
+
+        grid.remove(Point3::new(1, 2, 1));
+        assert_eq!(grid.bounds(), Some((Point3::new(0, 0, 1), Point3::new(2, 1, 2))));
+        assert_eq!(grid.render_slice(Axis::Z, 1, draw), ".#.\n.#.\n");
+    }
+
+}
@@ -0,0 +1,234 @@
+use regex::Regex;
+
+const BASE_URL: &str = "https://adventofcode.com";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Markdown,
+    Text,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Token<'a> {
+    Open(&'a str, &'a str),
+    Close(&'a str),
+    Text(&'a str),
+}
+
+pub fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let regex = Regex::new(r"<(/?)([a-zA-Z][a-zA-Z0-9]*)([^>]*)>").unwrap();
+    let mut tokens = Vec::new();
+    let mut last = 0;
+    for captures in regex.captures_iter(html) {
+        let whole = captures.get(0).unwrap();
+        if whole.start() > last {
+            tokens.push(Token::Text(&html[last..whole.start()]));
+        }
+        let name = captures.get(2).unwrap().as_str();
+        if captures[1].is_empty() {
+            tokens.push(Token::Open(name, captures.get(3).unwrap().as_str()));
+        } else {
+            tokens.push(Token::Close(name));
+        }
+        last = whole.end();
+    }
+    if last < html.len() {
+        tokens.push(Token::Text(&html[last..]));
+    }
+    tokens
+}
+
+pub fn attr<'a>(attrs: &'a str, name: &str) -> Option<&'a str> {
+    let regex = Regex::new(format!(r#"{name}="([^"]*)""#).as_str()).unwrap();
+    regex.captures(attrs).map(|c| c.get(1).unwrap().as_str())
+}
+
+pub fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+pub fn strip_tags(html: &str) -> String {
+    tokenize(html).iter()
+        .filter_map(|token| match token {
+            Token::Text(text) => Some(unescape(text)),
+            _ => None,
+        })
+        .collect()
+}
+
+pub fn render(html: &str, format: Format) -> String {
+    let mut out = String::new();
+    let mut pre = 0;
+    let mut code = 0;
+    let mut links: Vec<Option<String>> = Vec::new();
+
+    for token in tokenize(html) {
+        match token {
+            Token::Text(text) => {
+                if pre > 0 {
+                    let text = unescape(text);
+                    match format {
+                        Format::Markdown => out.push_str(text.as_str()),
+                        Format::Text => out.push_str(text.replace('\n', "\n    ").as_str()),
+                    }
+                } else if !text.trim().is_empty() || !(out.is_empty() || out.ends_with('\n')) {
+                    out.push_str(unescape(text).replace('\n', " ").as_str());
+                }
+            },
+            Token::Open(name, attrs) => match name {
+                "h2" => {
+                    block_break(&mut out);
+                    if format == Format::Markdown {
+                        out.push_str("## ");
+                    }
+                },
+                "p" => block_break(&mut out),
+                "pre" => {
+                    block_break(&mut out);
+                    pre += 1;
+                    match format {
+                        Format::Markdown => out.push_str("```\n"),
+                        Format::Text => out.push_str("    "),
+                    }
+                },
+                "code" if pre == 0 => {
+                    code += 1;
+                    if format == Format::Markdown {
+                        out.push('`');
+                    }
+                },
+                "em" if pre == 0 => match format {
+                    Format::Markdown if code == 0 => out.push('*'),
+                    Format::Text => out.push_str(BOLD),
+                    _ => {},
+                },
+                "a" => {
+                    let href = attr(attrs, "href").map(|href| {
+                        if href.starts_with('/') { format!("{BASE_URL}{href}") } else { href.to_string() }
+                    });
+                    if format == Format::Markdown && href.is_some() {
+                        out.push('[');
+                    }
+                    links.push(href);
+                },
+                "ul" => block_break(&mut out),
+                "li" => {
+                    line_break(&mut out);
+                    out.push_str(if format == Format::Markdown { "- " } else { "  - " });
+                },
+                "br" => out.push('\n'),
+                _ => {},
+            },
+            Token::Close(name) => match name {
+                "h2" | "p" | "ul" => block_break(&mut out),
+                "pre" => {
+                    pre -= 1;
+                    if format == Format::Text {
+                        let trimmed = out.trim_end_matches(' ').len();
+                        out.truncate(trimmed);
+                    }
+                    line_break(&mut out);
+                    if format == Format::Markdown {
+                        out.push_str("```\n");
+                    }
+                    block_break(&mut out);
+                },
+                "code" if pre == 0 => {
+                    code -= 1;
+                    if format == Format::Markdown {
+                        out.push('`');
+                    }
+                },
+                "em" if pre == 0 => match format {
+                    Format::Markdown if code == 0 => out.push('*'),
+                    Format::Text => out.push_str(RESET),
+                    _ => {},
+                },
+                "a" => {
+                    if let Some(Some(href)) = links.pop()
+                        && format == Format::Markdown {
+                        out.push_str(format!("]({href})").as_str());
+                    }
+                },
+                "li" => line_break(&mut out),
+                _ => {},
+            },
+        }
+    }
+
+    out.trim().to_string()
+}
+
+fn line_break(out: &mut String) {
+    let trimmed = out.trim_end_matches(' ').len();
+    out.truncate(trimmed);
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+}
+
+fn block_break(out: &mut String) {
+    line_break(out);
+    if !out.is_empty() && !out.ends_with("\n\n") {
+        out.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const HTML: &str = r#"<article class="day-desc"><h2>--- Day 1: Historian Hysteria ---</h2>
+<p>The <em>Chief Historian</em> is always present &amp; <a href="/2024/events">accounted</a> for.</p>
+<pre><code>3   4
+4   3
+</code></pre>
+<p>The answer is <code><em>11</em></code>.</p>
+<ul>
+<li>One</li>
+<li>Two</li>
+</ul>
+</article>"#;
+
+    #[test]
+    fn markdown() {
+        assert_eq!(render(HTML, Format::Markdown), "## --- Day 1: Historian Hysteria ---
+
+The *Chief Historian* is always present & [accounted](https://adventofcode.com/2024/events) for.
+
+```
+3   4
+4   3
+```
+
+The answer is `11`.
+
+- One
+- Two");
+    }
+
+    #[test]
+    fn text() {
+        assert_eq!(render(HTML, Format::Text), "--- Day 1: Historian Hysteria ---
+
+The \x1b[1mChief Historian\x1b[0m is always present & accounted for.
+
+    3   4
+    4   3
+
+The answer is \x1b[1m11\x1b[0m.
+
+  - One
+  - Two");
+    }
+
+}
@@ -0,0 +1,170 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use itertools::Itertools;
+use crate::aocday::AocInput;
+
+const TOP_NUMBERS: usize = 5;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Report {
+    pub lines: usize,
+    pub min_width: usize,
+    pub max_width: usize,
+    pub histogram: BTreeMap<char, usize>,
+    pub int_count: usize,
+    pub int_range: Option<(i128, i128)>,
+    pub negatives: bool,
+    pub largest: Vec<i128>,
+    pub oversized: Vec<String>,
+    pub sections: Vec<usize>,
+    pub grid: Option<(usize, usize)>,
+}
+
+impl Report {
+
+    pub fn new(input: &AocInput) -> Self {
+        let widths = input.lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| line.chars().count())
+            .collect_vec();
+        let histogram = input.raw().chars()
+            .filter(|c| *c != '\n')
+            .counts()
+            .into_iter()
+            .collect();
+
+        // Tokens come back as strings so a value too big even for i128 is
+        // reported rather than failing the whole scan.
+        let tokens: Vec<String> = input.ints::<String>().expect("Parsing a String cannot fail");
+        let (ints, oversized): (Vec<_>, Vec<_>) = tokens.iter().partition(|token| token.parse::<i128>().is_ok());
+        let ints: Vec<i128> = ints.into_iter().map(|token| token.parse().unwrap()).collect();
+        let oversized: Vec<String> = oversized.into_iter().cloned().collect();
+        let int_range = ints.iter().copied().minmax().into_option();
+        let largest = ints.iter()
+            .copied()
+            .sorted_by_key(|n| std::cmp::Reverse(n.unsigned_abs()))
+            .dedup()
+            .take(TOP_NUMBERS)
+            .collect();
+
+        let sections = input.sections().iter().map(|section| section.lines().count()).collect_vec();
+        let grid = (sections.len() == 1 && widths.len() > 1 && widths.iter().all_equal() && widths[0] > 1)
+            .then(|| (widths[0], widths.len()));
+
+        Self {
+            lines: input.lines().count(),
+            min_width: widths.iter().copied().min().unwrap_or(0),
+            max_width: widths.iter().copied().max().unwrap_or(0),
+            histogram,
+            int_count: tokens.len(),
+            int_range,
+            negatives: tokens.iter().any(|token| token.starts_with('-')),
+            largest,
+            oversized,
+            sections,
+            grid,
+        }
+    }
+
+    pub fn is_ragged(&self) -> bool {
+        self.min_width != self.max_width
+    }
+
+    pub fn overflows_i32(&self) -> bool {
+        !self.oversized.is_empty()
+            || self.int_range.is_some_and(|(min, max)| min < i32::MIN as i128 || max > i32::MAX as i128)
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "Lines    : {}", self.lines).unwrap();
+        if self.is_ragged() {
+            writeln!(out, "Width    : {}..={} (ragged)", self.min_width, self.max_width).unwrap();
+        } else {
+            writeln!(out, "Width    : {}", self.max_width).unwrap();
+        }
+
+        match self.grid {
+            Some((width, height)) => writeln!(out, "Grid     : {width}x{height}").unwrap(),
+            None => writeln!(out, "Grid     : no").unwrap(),
+        }
+
+        if self.sections.len() > 1 {
+            writeln!(out, "Sections : {} ({} lines)", self.sections.len(), self.sections.iter().join(", ")).unwrap();
+        }
+
+        let chars = self.histogram.iter()
+            .sorted_by_key(|(c, count)| (std::cmp::Reverse(**count), **c))
+            .map(|(c, count)| format!("{}:{count}", display_char(*c)))
+            .join(" ");
+        writeln!(out, "Chars    : {chars}").unwrap();
+
+        let negatives = if self.negatives { "has negatives" } else { "no negatives" };
+        match self.int_range {
+            Some((min, max)) => {
+                writeln!(out, "Integers : {} in {min}..={max}, {negatives}", self.int_count).unwrap();
+                writeln!(out, "Largest  : {}", self.largest.iter().join(", ")).unwrap();
+            },
+            None if self.int_count > 0 => writeln!(out, "Integers : {}, {negatives}", self.int_count).unwrap(),
+            None => writeln!(out, "Integers : none").unwrap(),
+        }
+        if let Some(digits) = self.oversized.iter().map(|token| token.trim_start_matches('-').len()).max() {
+            writeln!(out, "Warning  : {} too big for i128 (up to {digits} digits), parse them as strings or big integers",
+                self.oversized.len()).unwrap();
+        } else if self.overflows_i32() {
+            writeln!(out, "Warning  : values exceed i32, use BigSigned/BigUnsigned rather than AocOutput::Num").unwrap();
+        }
+        out
+    }
+
+}
+
+fn display_char(c: char) -> String {
+    match c {
+        ' ' => "' '".to_string(),
+        '\t' => "\\t".to_string(),
+        c => c.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn grid() {
+        let report = Report::new(&AocInput::dedent("....#
+                                                    .#..#
+                                                    ..^.."));
+        assert_eq!(report.grid, Some((5, 3)));
+        assert_eq!(report.histogram[&'.'], 11);
+        assert_eq!(report.int_range, None);
+    }
+
+    #[test]
+    fn numbers() {
+        let report = Report::new(&AocInput::dedent("p=0,4 v=3,-3
+
+                                                    190: 10 3000000000"));
+        assert_eq!(report.sections, vec![1, 1]);
+        assert!(report.is_ragged());
+        assert!(report.negatives);
+        assert!(report.overflows_i32());
+        assert_eq!(report.largest, vec![3000000000, 190, 10, 4, 3]);
+        assert!(report.oversized.is_empty());
+    }
+
+    #[test]
+    fn oversized() {
+        let huge = "1".repeat(40);
+        let report = Report::new(&AocInput::new(format!("5 -{huge} 7\n").as_str()));
+        assert_eq!(report.int_count, 3);
+        assert_eq!(report.int_range, Some((5, 7)));
+        assert_eq!(report.oversized, vec![format!("-{huge}")]);
+        assert!(report.negatives);
+        assert!(report.overflows_i32());
+        assert!(report.render().contains("Warning  : 1 too big for i128 (up to 40 digits)"));
+    }
+
+}
@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::time::Duration;
+use anyhow::{anyhow, Error};
+use chrono::{DateTime, TimeZone, Utc};
+use chrono_tz::America::New_York;
+use itertools::Itertools;
+use serde::Deserialize;
+use crate::client::{AocClient, CachePolicy};
+use crate::YEAR;
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Leaderboard {
+    pub owner_id: u64,
+    pub event: String,
+    pub members: HashMap<String, Member>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Member {
+    pub id: u64,
+    pub name: Option<String>,
+    pub stars: u32,
+    pub local_score: u32,
+    #[serde(default)]
+    pub completion_day_level: HashMap<String, HashMap<String, Star>>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct Star {
+    pub get_star_ts: i64,
+}
+
+impl Leaderboard {
+
+    pub fn parse(json: &str) -> Result<Self, Error> {
+        serde_json::from_str(json).map_err(|e| anyhow!("Invalid leaderboard JSON: {e}"))
+    }
+
+    pub fn fetch(client: &AocClient, id: u64) -> Result<Self, Error> {
+        let path = format!("/{YEAR}/leaderboard/private/view/{id}.json");
+        let response = client.get(path.as_str(), CachePolicy::MaxAge(REFRESH_INTERVAL))?;
+        Self::parse(response.body.as_str())
+    }
+
+    pub fn year(&self) -> i32 {
+        self.event.parse().unwrap_or(YEAR)
+    }
+
+    pub fn standings(&self) -> Vec<&Member> {
+        self.members.values()
+            .sorted_by(|a, b| b.local_score.cmp(&a.local_score)
+                .then(b.stars.cmp(&a.stars))
+                .then(a.id.cmp(&b.id)))
+            .collect()
+    }
+
+    pub fn render_standings(&self) -> String {
+        let standings = self.standings();
+        let width = standings.iter().map(|member| member.display_name().len()).max().unwrap_or(0);
+
+        let mut out = String::new();
+        writeln!(out, "{:>4} {:>5} {:>5}  {:<width$}  {}", "", "Score", "Stars", "Name", day_header()).unwrap();
+        for (rank, member) in standings.iter().enumerate() {
+            let stars: String = (1..=25)
+                .map(|day| match member.stars_on(day) {
+                    2 => '*',
+                    1 => '+',
+                    _ => '.',
+                })
+                .collect();
+            writeln!(out, "{:>3}) {:>5} {:>5}  {:<width$}  {stars}",
+                rank + 1, member.local_score, member.stars, member.display_name()).unwrap();
+        }
+        out
+    }
+
+    pub fn render_day(&self, day: u8) -> Result<String, Error> {
+        let unlock = unlock_time(self.year(), day)?;
+        let members = self.members.values()
+            .filter(|member| member.star_time(day, 1).is_some())
+            .sorted_by_key(|member| (member.star_time(day, 2).is_none(), member.star_time(day, 2), member.star_time(day, 1)))
+            .collect_vec();
+        let width = members.iter().map(|member| member.display_name().len()).max().unwrap_or(0).max(4);
+
+        let mut out = String::new();
+        writeln!(out, "Day {day}").unwrap();
+        writeln!(out, "  {:<width$}  {:>10}  {:>10}  {:>10}", "Name", "Part 1", "Part 2", "Delta").unwrap();
+        for member in members {
+            let part1 = member.star_time(day, 1).map(|ts| ts - unlock);
+            let part2 = member.star_time(day, 2).map(|ts| ts - unlock);
+            let delta = part1.zip(part2).map(|(part1, part2)| part2 - part1);
+            writeln!(out, "  {:<width$}  {:>10}  {:>10}  {:>10}",
+                member.display_name(), format_duration(part1), format_duration(part2), format_duration(delta)).unwrap();
+        }
+        Ok(out)
+    }
+
+}
+
+impl Member {
+
+    pub fn display_name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| format!("(anonymous user #{})", self.id))
+    }
+
+    pub fn stars_on(&self, day: u8) -> usize {
+        self.completion_day_level.get(&day.to_string()).map_or(0, HashMap::len)
+    }
+
+    pub fn star_time(&self, day: u8, part: u8) -> Option<DateTime<Utc>> {
+        self.completion_day_level.get(&day.to_string())
+            .and_then(|parts| parts.get(&part.to_string()))
+            .and_then(|star| Utc.timestamp_opt(star.get_star_ts, 0).single())
+    }
+
+}
+
+// Accepts `<id> [day]`, or just `[day]` when the ID comes from $AOC_LEADERBOARD.
+// Leaderboard IDs are user IDs, far above 25, so a lone 1 to 25 is a day.
+pub fn parse_args(args: &[String], default_id: Option<&str>) -> Result<(u64, Option<u8>), Error> {
+    let usage = || anyhow!("Usage: leaderboard <id> [day], or set AOC_LEADERBOARD and use leaderboard [day]");
+    let (id, day) = match (args, default_id) {
+        ([], Some(id)) => (id, None),
+        ([day], Some(id)) if matches!(day.parse::<u8>(), Ok(1..=25)) => (id, Some(day.as_str())),
+        ([id], _) => (id.as_str(), None),
+        ([id, day], _) => (id.as_str(), Some(day.as_str())),
+        _ => return Err(usage()),
+    };
+
+    let id = id.parse::<u64>().map_err(|_| usage())?;
+    let day = match day.map(|day| (day, day.parse::<u8>())) {
+        Some((_, Ok(day @ 1..=25))) => Some(day),
+        Some((day, _)) => return Err(anyhow!("Invalid day {day:?}, expected 1 to 25")),
+        None => None,
+    };
+    Ok((id, day))
+}
+
+pub fn unlock_time(year: i32, day: u8) -> Result<DateTime<Utc>, Error> {
+    if !(1..=25).contains(&day) {
+        return Err(anyhow!("Day {day} is not a puzzle day, expected 1 to 25"));
+    }
+    New_York.with_ymd_and_hms(year, 12, day as u32, 0, 0, 0)
+        .single()
+        .map(|unlock| unlock.with_timezone(&Utc))
+        .ok_or_else(|| anyhow!("No unlock time for {year} day {day}"))
+}
+
+fn day_header() -> String {
+    (1..=25).map(|day| char::from_digit(day % 10, 10).unwrap()).collect()
+}
+
+fn format_duration(duration: Option<chrono::Duration>) -> String {
+    match duration {
+        Some(duration) => {
+            let secs = duration.num_seconds();
+            format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+        },
+        None => "-".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const JSON: &str = r#"{
+        "owner_id": 1, "event": "2024",
+        "members": {
+            "1": {"id": 1, "name": "Alice", "stars": 3, "local_score": 7, "global_score": 0, "last_star_ts": 1733116520,
+                "completion_day_level": {
+                    "1": {"1": {"get_star_ts": 1733029805, "star_index": 1}, "2": {"get_star_ts": 1733030225, "star_index": 2}},
+                    "2": {"1": {"get_star_ts": 1733116520, "star_index": 3}}
+                }},
+            "2": {"id": 2, "name": null, "stars": 2, "local_score": 3, "global_score": 0, "last_star_ts": 1733040000,
+                "completion_day_level": {
+                    "1": {"1": {"get_star_ts": 1733035000, "star_index": 4}, "2": {"get_star_ts": 1733040000, "star_index": 5}}
+                }}
+        }
+    }"#;
+
+    #[test]
+    fn standings() {
+        let leaderboard = Leaderboard::parse(JSON).expect("Parse");
+        assert_eq!(leaderboard.render_standings(), [
+            "     Score Stars  Name                 1234567890123456789012345",
+            "  1)     7     3  Alice                *+.......................",
+            "  2)     3     2  (anonymous user #2)  *........................",
+            "",
+        ].join("\n"));
+    }
+
+    #[test]
+    fn day() {
+        let leaderboard = Leaderboard::parse(JSON).expect("Parse");
+        assert!(leaderboard.render_day(0).is_err());
+        assert!(leaderboard.render_day(26).is_err());
+        assert_eq!(leaderboard.render_day(1).expect("Render"), [
+            "Day 1",
+            "  Name                     Part 1      Part 2       Delta",
+            "  Alice                   0:10:05     0:17:05     0:07:00",
+            "  (anonymous user #2)     1:36:40     3:00:00     1:23:20",
+            "",
+        ].join("\n"));
+        assert!(Leaderboard::parse("{}").is_err());
+    }
+
+    #[test]
+    fn args() {
+        let args = |args: &[&str], default_id| parse_args(&args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>(), default_id);
+        assert_eq!(args(&["123456"], None).unwrap(), (123456, None));
+        assert_eq!(args(&["123456", "6"], None).unwrap(), (123456, Some(6)));
+        assert_eq!(args(&[], Some("123456")).unwrap(), (123456, None));
+        assert_eq!(args(&["6"], Some("123456")).unwrap(), (123456, Some(6)));
+        assert_eq!(args(&["654321"], Some("123456")).unwrap(), (654321, None));
+        assert_eq!(args(&["654321", "25"], Some("123456")).unwrap(), (654321, Some(25)));
+        assert_eq!(args(&["6"], None).unwrap(), (6, None));
+
+        assert!(args(&[], None).is_err());
+        assert!(args(&["board"], None).is_err());
+        assert!(args(&["123456", "26"], None).is_err());
+        assert!(args(&["123456", "0"], Some("123456")).is_err());
+        assert!(args(&["123456", "6", "7"], None).is_err());
+    }
+
+}
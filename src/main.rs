@@ -1,9 +1,13 @@
 #![feature(iter_collect_into)]
 #![feature(mixed_integer_ops_unsigned_sub)]
 #![feature(unsigned_signed_diff)]
+#![cfg_attr(test, feature(test))]
 #![allow(dead_code)]
 #![allow(unused_imports)]
 
+#[cfg(test)]
+extern crate test;
+
 mod day1;
 mod day2;
 mod day3;
@@ -21,7 +25,20 @@ mod util;
 mod aocday;
 mod days;
 mod grid;
+mod sparse;
+mod grid3;
 mod math;
+mod session;
+mod client;
+mod html;
+mod puzzle;
+mod examples;
+mod leaderboard;
+mod calendar;
+mod crypt;
+mod scan;
+mod inspect;
+mod results;
 
 use std::{any, env};
 use std::fs;
@@ -32,17 +49,24 @@ use anyhow::{anyhow, Error};
 use regex::Regex;
 use reqwest::blocking::Client;
 use aocday::AocDay;
+use client::AocClient;
+use html::Format;
+use puzzle::Puzzle;
+use leaderboard::Leaderboard;
+use crypt::InputKey;
 use days::build_days;
 use RunMode::Unlocked;
 use timer::Timer;
-use util::{end_day, read_input};
-use crate::aocday::{AocInfo, AocInput, AocOutput};
+use util::{end_day, read_input, read_plain_input};
+use crate::aocday::{AocInfo, AocInput, AocOutput, Anomaly};
+use crate::results::{ResultCache, ResultKey};
 use crate::RunMode::{All, Single, Today};
 
 const YEAR: i32 = 2024;
 const RUN_DAY: Option<u8> = None;
 const RUN_MODE: RunMode = Unlocked;
 const REDACT: bool = true;
+const CACHE_RESULTS: bool = false;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 enum RunMode {
@@ -53,7 +77,134 @@ enum RunMode {
 }
 
 fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        None | Some("run") => run_days(CACHE_RESULTS || args.iter().any(|arg| arg == "--cache")),
+        Some("whoami") => whoami(),
+        Some("show") => show(&args[1..]),
+        Some("examples") => extract_examples(&args[1..]),
+        Some("leaderboard") => show_leaderboard(&args[1..]),
+        Some("calendar") => show_calendar(&args[1..]),
+        Some("keygen") => keygen(),
+        Some("encrypt-inputs") => encrypt_inputs(),
+        Some("inspect") => inspect_input(&args[1..]),
+        Some(command) => Err(anyhow!("Unknown command: {command}")),
+    };
+
+    if let Err(e) = result {
+        eprintln!("{e:?}");
+        std::process::exit(1);
+    }
+}
+
+fn whoami() -> Result<(), Error> {
+    let client = AocClient::new()?;
+    let user = session::whoami(&client)?;
+    println!("Logged in as {user} (token from {})", client.session().source());
+    Ok(())
+}
+
+fn show(args: &[String]) -> Result<(), Error> {
+    let day = args.first()
+        .and_then(|day| day.parse::<u8>().ok())
+        .ok_or_else(|| anyhow!("Usage: show <day> [--markdown] [--refresh]"))?;
+    let format = if args.iter().any(|arg| arg == "--markdown") { Format::Markdown } else { Format::Text };
+    let refresh = args.iter().any(|arg| arg == "--refresh");
+
+    let puzzle = Puzzle::load(&AocClient::new()?, day, refresh)?;
+    println!("{}", puzzle.render(format));
+    Ok(())
+}
+
+fn extract_examples(args: &[String]) -> Result<(), Error> {
+    let day = args.first()
+        .and_then(|day| day.parse::<u8>().ok())
+        .ok_or_else(|| anyhow!("Usage: examples <day> [saved.html]"))?;
+
+    let html = match args.get(1) {
+        Some(path) => fs::read_to_string(path)?,
+        None => Puzzle::cached(day)
+            .ok_or_else(|| anyhow!("Day {day}: puzzle page not cached, run `show {day}` first"))?
+            .html()
+            .to_string(),
+    };
+
+    let examples = examples::extract(html.as_str());
+    if examples.is_empty() {
+        return Err(anyhow!("Day {day}: no examples found"));
+    }
+
+    let dir = examples::write_fixtures(day, &examples)?;
+    for (idx, example) in examples.iter().enumerate() {
+        let answers = example.answers.iter()
+            .enumerate()
+            .filter_map(|(part, answer)| answer.as_ref().map(|answer| format!("part {}: {answer}", part + 1)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        println!("example{}: {} lines ({answers})", idx + 1, example.input.lines().count());
+    }
+    println!("Wrote {} example(s) to {}", examples.len(), dir.display());
+    Ok(())
+}
+
+fn show_leaderboard(args: &[String]) -> Result<(), Error> {
+    let (id, day) = leaderboard::parse_args(args, env::var("AOC_LEADERBOARD").ok().as_deref())?;
+    let leaderboard = Leaderboard::fetch(&AocClient::new()?, id)?;
+    match day {
+        Some(day) => print!("{}", leaderboard.render_day(day)?),
+        None => print!("{}", leaderboard.render_standings()),
+    }
+    Ok(())
+}
+
+fn show_calendar(args: &[String]) -> Result<(), Error> {
+    let client = if args.iter().any(|arg| arg == "--fetch") { Some(AocClient::new()?) } else { None };
+    let events = calendar::events_page(client.as_ref())?;
+    let last_day = end_day(YEAR).unwrap_or(25).min(25);
+
+    let calendar = calendar::build(&build_days(), last_day, events.as_deref(), Puzzle::cached);
+    print!("{}", calendar::render(&calendar, redact));
+    Ok(())
+}
+
+fn keygen() -> Result<(), Error> {
+    let path = InputKey::generate().save()?;
+    println!("Wrote input key to {}; share it with the team out of band", path.display());
+    Ok(())
+}
+
+fn encrypt_inputs() -> Result<(), Error> {
+    let key = InputKey::load()?
+        .ok_or_else(|| anyhow!("No input key: set AOC_INPUT_KEY or run `keygen`"))?;
+
+    for day in 1..=25 {
+        if let Some(input) = read_plain_input(day)? {
+            match crypt::write_encrypted_input(&key, day, input.as_str())? {
+                Some(path) => println!("Day {day}: {}", path.display()),
+                None => println!("Day {day}: unchanged"),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn inspect_input(args: &[String]) -> Result<(), Error> {
+    let arg = args.first().ok_or_else(|| anyhow!("Usage: inspect <day|path>"))?;
+    let input = match arg.parse::<u8>() {
+        Ok(day) => read_input(day)?,
+        Err(_) => AocInput::from(fs::read_to_string(arg)?),
+    };
+
+    for anomaly in input.anomalies() {
+        println!("Anomaly  : {anomaly}");
+    }
+    print!("{}", inspect::Report::new(&input).render());
+    Ok(())
+}
+
+fn run_days(cache_results: bool) -> Result<(), Error> {
     let today = end_day(YEAR);
+    let mut results = if cache_results { Some(ResultCache::load()?) } else { None };
     let mut days = build_days();
 
     let mut timer = Timer::new();
@@ -79,42 +230,76 @@ fn main() {
             continue;
         }
 
-        match timer.time_with_result(|| day.init(input.unwrap())) {
-            Ok((_, duration)) => {
-                let (_, day_name) = day.info().into();
-                if day_name.is_empty() {
-                    println!("Day {day_num}");
-                } else {
-                    println!("Day {day_num}: {day_name}");
-                }
+        let input = input.unwrap();
+        let anomalies = input.anomalies().to_vec();
+        let keys = results.as_ref().map(|_| ResultKey::parts(day_num, &input));
 
-                println!("  Init  : ({:?})", duration);
-            },
+        if let Some([Some((part1, part1_duration)), Some((part2, part2_duration))]) =
+            results.as_ref().zip(keys).map(|(results, keys)| keys.map(|key| results.get(&key))) {
+            print_day_header(day.as_ref(), &anomalies);
+            println!("  Init  : (cached)");
+            print_part(1, Ok(part1), part1_duration, true);
+            print_part(2, Ok(part2), part2_duration, true);
+            continue;
+        }
+
+        // The header carries the input anomalies, which matter most when init fails.
+        print_day_header(day.as_ref(), &anomalies);
+        match timer.time_with_result(|| day.init(input)) {
+            Ok((_, duration)) => println!("  Init  : ({:?})", duration),
             Err(e) => {
-                println!("Day {day_num}: {e}");
+                println!("  Init  : (Error)\n    {}", format!("{e:?}").replace('\n', "\n    "));
                 continue;
             },
         }
 
         let (part1, part1_duration) = timer.time_with_return(|| day.part1());
-        print_part(1, part1, part1_duration);
+        if let (Some(results), Some(keys), Ok(output)) = (results.as_mut(), &keys, &part1) {
+            results.insert(&keys[0], output, part1_duration);
+        }
+        print_part(1, part1, part1_duration, false);
 
         let (part2, part2_duration) = timer.time_with_return(|| day.part2());
-        print_part(2, part2, part2_duration);
+        if let (Some(results), Some(keys), Ok(output)) = (results.as_mut(), &keys, &part2) {
+            results.insert(&keys[1], output, part2_duration);
+        }
+        print_part(2, part2, part2_duration, false);
     }
 
     println!("Total: {:?}", timer.duration);
+
+    if let Some(results) = results {
+        results.save()?;
+    }
+    Ok(())
+}
+
+fn print_day_header(day: &dyn AocDay, anomalies: &[Anomaly]) {
+    let (day_num, mut day_name): (u8, String) = day.info().into();
+    if day_name.is_empty() {
+        day_name = puzzle::title(day_num).unwrap_or_default();
+    }
+    if day_name.is_empty() {
+        println!("Day {day_num}");
+    } else {
+        println!("Day {day_num}: {day_name}");
+    }
+
+    for anomaly in anomalies {
+        println!("  Input : {anomaly}");
+    }
 }
 
-fn print_part(part_number: u8, result: Result<AocOutput, Error>, duration: Duration) {
+fn print_part(part_number: u8, result: Result<AocOutput, Error>, duration: Duration, cached: bool) {
+    let timing = if cached { format!("{:?}, cached", duration) } else { format!("{:?}", duration) };
     match result {
         Ok(solution) => {
             if let AocOutput::Multiline(text) = solution {
-                println!("  Part {}: ({:?})\n    {}", part_number, duration, redact(text).replace('\n', "\n    "));
+                println!("  Part {}: ({})\n    {}", part_number, timing, redact(text).replace('\n', "\n    "));
             } else if let AocOutput::Unimplemented = solution {
                 println!("  Part {}: {}", part_number, redact(solution.to_string()));
             } else {
-                println!("  Part {}: {} ({:?})", part_number, redact(solution.to_string()), duration);
+                println!("  Part {}: {} ({})", part_number, redact(solution.to_string()), timing);
             }
         },
         Err(e) => {
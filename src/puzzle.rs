@@ -0,0 +1,127 @@
+use std::time::Duration;
+use anyhow::{anyhow, Error};
+use regex::Regex;
+use crate::client::{self, AocClient, CachePolicy};
+use crate::html::{self, Format};
+use crate::YEAR;
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Clone, Debug)]
+pub struct Puzzle {
+    day: u8,
+    title: Option<String>,
+    parts: Vec<String>,
+    answers: Vec<String>,
+    html: String,
+}
+
+impl Puzzle {
+
+    pub fn parse(day: u8, html: &str) -> Self {
+        let title = Regex::new(r"<h2>--- Day \d+: (.*?) ---</h2>").unwrap()
+            .captures(html)
+            .map(|c| html::unescape(&c[1]));
+
+        let parts = Regex::new(r#"(?s)<article class="day-desc">(.*?)</article>"#).unwrap()
+            .captures_iter(html)
+            .map(|c| c[1].to_string())
+            .collect();
+
+        let answers = Regex::new(r"Your puzzle answer was <code>(.*?)</code>").unwrap()
+            .captures_iter(html)
+            .map(|c| html::unescape(&c[1]))
+            .collect();
+
+        Self { day, title, parts, answers, html: html.to_string() }
+    }
+
+    pub fn load(client: &AocClient, day: u8, refresh: bool) -> Result<Self, Error> {
+        let path = path(day);
+
+        if !refresh
+            && let Some(cached) = client.cached(path.as_str()) {
+            let puzzle = Self::parse(day, cached.body.as_str());
+            if puzzle.is_complete() {
+                return Ok(puzzle);
+            }
+        }
+
+        if refresh {
+            client.invalidate(path.as_str())?;
+        }
+
+        let response = client.get(path.as_str(), CachePolicy::MaxAge(REFRESH_INTERVAL))?;
+        let puzzle = Self::parse(day, response.body.as_str());
+        if puzzle.parts.is_empty() {
+            return Err(anyhow!("Day {day}: no puzzle description found"));
+        }
+        Ok(puzzle)
+    }
+
+    pub fn cached(day: u8) -> Option<Self> {
+        client::cached(path(day).as_str()).map(|response| Self::parse(day, response.body.as_str()))
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    pub fn html(&self) -> &str {
+        self.html.as_str()
+    }
+
+    pub fn parts(&self) -> &[String] {
+        &self.parts
+    }
+
+    pub fn answers(&self) -> &[String] {
+        &self.answers
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.parts.len() >= 2
+    }
+
+    pub fn render(&self, format: Format) -> String {
+        self.parts.iter()
+            .map(|part| html::render(part, format))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+}
+
+pub fn title(day: u8) -> Option<String> {
+    Puzzle::cached(day).and_then(|puzzle| puzzle.title)
+}
+
+fn path(day: u8) -> String {
+    format!("/{YEAR}/day/{day}")
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const HTML: &str = r#"<main>
+<article class="day-desc"><h2>--- Day 6: Guard Gallivant ---</h2><p>Part one.</p></article>
+<p>Your puzzle answer was <code>41</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2><p>Part two.</p></article>
+</main>"#;
+
+    #[test]
+    fn parse() {
+        let puzzle = Puzzle::parse(6, HTML);
+        assert_eq!(puzzle.title(), Some("Guard Gallivant"));
+        assert!(puzzle.is_complete());
+        assert_eq!(puzzle.answers(), ["41"]);
+        assert_eq!(puzzle.render(Format::Markdown), "## --- Day 6: Guard Gallivant ---\n\nPart one.\n\n## --- Part Two ---\n\nPart two.");
+    }
+
+}
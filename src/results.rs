@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+use std::{env, fs};
+use std::path::PathBuf;
+use std::time::Duration;
+use anyhow::Error;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use crate::aocday::{AocInput, AocOutput};
+use crate::client::cache_dir;
+
+const RESULTS_FILE: &str = "results.json";
+
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+pub struct ResultKey {
+    pub day: u8,
+    pub part: u8,
+    pub input_hash: u64,
+    pub build_hash: u64,
+}
+
+impl ResultKey {
+    pub fn new(day: u8, part: u8, input: &AocInput) -> Self {
+        Self { day, part, input_hash: fnv1a(input.raw().as_bytes()), build_hash: build_hash(day) }
+    }
+
+    // Both parts share the hashes, and build_hash reads the sources, so work them out once.
+    pub fn parts(day: u8, input: &AocInput) -> [Self; 2] {
+        let part1 = Self::new(day, 1, input);
+        [part1, Self { part: 2, ..part1 }]
+    }
+
+    fn id(&self) -> String {
+        format!("day{}.part{}", self.day, self.part)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Entry {
+    input_hash: String,
+    build_hash: String,
+    output: AocOutput,
+    nanos: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct ResultCache {
+    path: PathBuf,
+    entries: HashMap<String, Entry>,
+}
+
+impl ResultCache {
+
+    pub fn load() -> Result<Self, Error> {
+        let path = cache_dir()?.join(RESULTS_FILE);
+        let entries = match fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(json.as_str()).unwrap_or_default(),
+            Err(_) => HashMap::new(),
+        };
+        Ok(Self { path, entries })
+    }
+
+    pub fn get(&self, key: &ResultKey) -> Option<(AocOutput, Duration)> {
+        self.entries.get(&key.id())
+            .filter(|entry| entry.input_hash == hex(key.input_hash) && entry.build_hash == hex(key.build_hash))
+            .map(|entry| (entry.output.clone(), Duration::from_nanos(entry.nanos)))
+    }
+
+    pub fn insert(&mut self, key: &ResultKey, output: &AocOutput, duration: Duration) {
+        if *output == AocOutput::Unimplemented {
+            self.entries.remove(&key.id());
+            return;
+        }
+        self.entries.insert(key.id(), Entry {
+            input_hash: hex(key.input_hash),
+            build_hash: hex(key.build_hash),
+            output: output.clone(),
+            nanos: duration.as_nanos() as u64,
+        });
+    }
+
+    pub fn save(&self) -> Result<(), Error> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(&self.entries)?)?;
+        Ok(())
+    }
+
+}
+
+// The day's own source plus every shared module, so a change to one day only
+// invalidates that day. Falls back to the binary when the sources are not around.
+fn build_hash(day: u8) -> u64 {
+    let day_file = Regex::new(r"^day\d+\.rs$").unwrap();
+    let sources = env::current_dir().ok()
+        .map(|dir| dir.join("src"))
+        .and_then(|dir| fs::read_dir(dir).ok())
+        .map(|entries| {
+            let mut files: Vec<(String, PathBuf)> = entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| (entry.file_name().to_string_lossy().to_string(), entry.path()))
+                .filter(|(name, _)| name.ends_with(".rs"))
+                .filter(|(name, _)| !day_file.is_match(name) || *name == format!("day{day}.rs"))
+                .collect();
+            files.sort();
+            files
+        })
+        .filter(|files| files.iter().any(|(name, _)| *name == format!("day{day}.rs")));
+
+    let mut bytes = Vec::new();
+    match sources {
+        Some(files) => for (name, path) in files {
+            bytes.extend(name.as_bytes());
+            bytes.extend(fs::read(path).unwrap_or_default());
+        },
+        None => bytes = env::current_exe().and_then(fs::read).unwrap_or_default(),
+    }
+    fnv1a(&bytes)
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x100000001b3))
+}
+
+fn hex(hash: u64) -> String {
+    format!("{hash:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn get_and_insert() {
+        let input = AocInput::new("3   4\n4   3\n");
+        let key = ResultKey::new(1, 1, &input);
+        let mut cache = ResultCache::default();
+        assert_eq!(cache.get(&key), None);
+
+        cache.insert(&key, &AocOutput::Num(11), Duration::from_micros(5));
+        assert_eq!(cache.get(&key), Some((AocOutput::Num(11), Duration::from_micros(5))));
+
+        let changed = ResultKey::new(1, 1, &AocInput::new("3   4\n4   4\n"));
+        assert_eq!(cache.get(&changed), None);
+        assert_eq!(cache.get(&ResultKey { build_hash: 0, ..key }), None);
+
+        cache.insert(&key, &AocOutput::Unimplemented, Duration::ZERO);
+        assert_eq!(cache.get(&key), None);
+
+        let [part1, part2] = ResultKey::parts(1, &input);
+        assert_eq!(part1, key);
+        assert_eq!(part2, ResultKey::new(1, 2, &input));
+    }
+
+}
@@ -0,0 +1,158 @@
+use std::fmt::Display;
+use std::str::FromStr;
+use anyhow::{anyhow, Error};
+use regex::Regex;
+
+#[macro_export]
+macro_rules! scan {
+    ($line:expr, $pattern:literal => $($ty:ty),+ $(,)?) => {{
+        static PATTERN: std::sync::OnceLock<$crate::scan::Pattern> = std::sync::OnceLock::new();
+        PATTERN.get_or_init(|| $crate::scan::Pattern::new($pattern).expect("Invalid scan pattern"))
+            .scan::<($($ty,)+)>($line)
+    }};
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Field<'a> {
+    pub index: usize,
+    pub col: usize,
+    pub text: &'a str,
+}
+
+impl Field<'_> {
+    pub fn parse<T>(&self) -> Result<T, Error> where T: FromStr, T::Err: Display {
+        self.text.parse().map_err(|e| {
+            anyhow!("column {}: cannot parse field {} {:?}: {e}", self.col + 1, self.index + 1, self.text)
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Pattern {
+    source: String,
+    literals: Vec<String>,
+    regex: Regex,
+}
+
+impl Pattern {
+
+    pub fn new(pattern: &str) -> Result<Self, Error> {
+        let literals: Vec<String> = pattern.split("{}").map(str::to_string).collect();
+        if literals.len() < 2 {
+            return Err(anyhow!("Scan pattern {pattern:?} has no {{}} fields"));
+        }
+
+        Ok(Self {
+            source: pattern.to_string(),
+            regex: Regex::new(format!("^{}$", regex_source(&literals)).as_str())?,
+            literals,
+        })
+    }
+
+    pub fn arity(&self) -> usize {
+        self.literals.len() - 1
+    }
+
+    pub fn fields<'a>(&self, line: &'a str) -> Result<Vec<Field<'a>>, Error> {
+        match self.regex.captures(line) {
+            Some(captures) => Ok(captures.iter()
+                .skip(1)
+                .enumerate()
+                .map(|(index, m)| {
+                    let m = m.unwrap();
+                    Field { index, col: line[..m.start()].chars().count(), text: m.as_str() }
+                })
+                .collect()),
+            None => Err(self.mismatch(line)),
+        }
+    }
+
+    pub fn check_arity<T: FromFields>(&self) -> Result<(), Error> {
+        if T::ARITY != self.arity() {
+            return Err(anyhow!("Scan pattern {:?} has {} fields, expected {}", self.source, self.arity(), T::ARITY));
+        }
+        Ok(())
+    }
+
+    pub fn scan<T: FromFields>(&self, line: &str) -> Result<T, Error> {
+        self.check_arity::<T>()?;
+        T::from_fields(&self.fields(line)?)
+    }
+
+    fn mismatch(&self, line: &str) -> Error {
+        for matched in (1..self.literals.len()).rev() {
+            let prefix = Regex::new(format!("^{}", regex_source(&self.literals[..matched])).as_str()).unwrap();
+            if let Some(m) = prefix.find(line) {
+                let rest = &line[m.end()..];
+                let col = line[..m.end()].chars().count() + 1;
+                return match self.literals[matched].as_str() {
+                    "" => anyhow!("column {col}: expected field {matched} then end of line, found {rest:?}"),
+                    literal => anyhow!("column {col}: expected field {matched} then {literal:?}, found {rest:?}"),
+                };
+            }
+        }
+        anyhow!("column 1: expected {:?}, found {line:?}", self.literals[0])
+    }
+
+}
+
+fn regex_source(literals: &[String]) -> String {
+    literals.iter()
+        .map(|literal| regex::escape(literal))
+        .collect::<Vec<_>>()
+        .join("(.*?)")
+}
+
+pub trait FromFields: Sized {
+    const ARITY: usize;
+
+    fn from_fields(fields: &[Field]) -> Result<Self, Error>;
+}
+
+macro_rules! impl_from_fields {
+    ($arity:literal => $($ty:ident $idx:tt),+) => {
+        impl<$($ty),+> FromFields for ($($ty,)+) where $($ty: FromStr, $ty::Err: Display),+ {
+            const ARITY: usize = $arity;
+
+            fn from_fields(fields: &[Field]) -> Result<Self, Error> {
+                Ok(($(fields[$idx].parse::<$ty>()?,)+))
+            }
+        }
+    };
+}
+
+impl_from_fields!(1 => A 0);
+impl_from_fields!(2 => A 0, B 1);
+impl_from_fields!(3 => A 0, B 1, C 2);
+impl_from_fields!(4 => A 0, B 1, C 2, D 3);
+impl_from_fields!(5 => A 0, B 1, C 2, D 3, E 4);
+impl_from_fields!(6 => A 0, B 1, C 2, D 3, E 4, F 5);
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn scan() {
+        let button = scan!("Button A: X+94, Y+34", "Button {}: X+{}, Y+{}" => char, i64, i64);
+        assert_eq!(button.expect("Scan"), ('A', 94, 34));
+
+        let (result, values) = scan!("190: 10 19", "{}: {}" => u64, String).expect("Scan");
+        assert_eq!((result, values.as_str()), (190, "10 19"));
+    }
+
+    #[test]
+    fn errors() {
+        let pattern = Pattern::new("Button {}: X+{}, Y+{}").expect("Pattern");
+        let err = pattern.scan::<(char, i64, i64)>("Button A: X=94, Y=34").unwrap_err();
+        assert_eq!(err.to_string(), "column 8: expected field 1 then \": X+\", found \"A: X=94, Y=34\"");
+
+        let err = pattern.scan::<(char, i64, i64)>("Button A: X+9x, Y+34").unwrap_err();
+        assert_eq!(err.to_string(), "column 13: cannot parse field 2 \"9x\": invalid digit found in string");
+
+        assert!(pattern.scan::<(char, i64)>("Button A: X+94, Y+34").is_err());
+        assert!(Pattern::new("no fields").is_err());
+    }
+
+}
@@ -0,0 +1,172 @@
+use std::{env, fs};
+use std::fmt::{Debug, Formatter};
+use std::path::{Path, PathBuf};
+use anyhow::{anyhow, Error};
+use regex::Regex;
+use crate::client::{AocClient, CachePolicy};
+use crate::YEAR;
+
+const SESSION_ENV: &str = "AOC_SESSION";
+const SESSION_FILE: &str = ".session_token";
+const CONFIG_DIR: &str = "aoc";
+const CONFIG_FILE: &str = "session_token";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TokenSource {
+    Env,
+    Project,
+    Config,
+}
+
+#[derive(Clone)]
+pub struct SessionToken {
+    token: String,
+    source: TokenSource,
+}
+
+impl SessionToken {
+
+    pub fn load() -> Result<Self, Error> {
+        if let Ok(token) = env::var(SESSION_ENV) {
+            return Self::parse(token.as_str(), TokenSource::Env);
+        }
+
+        let project_path = env::current_dir()?.join(SESSION_FILE);
+        if project_path.exists() {
+            return Self::read(&project_path, TokenSource::Project);
+        }
+
+        if let Some(config_path) = config_path()
+            && config_path.exists() {
+            return Self::read(&config_path, TokenSource::Config);
+        }
+
+        Err(anyhow!("No session token found: set {SESSION_ENV}, or create {SESSION_FILE} or {}",
+            config_path().map_or_else(|| "a config file".to_string(), |p| p.display().to_string())))
+    }
+
+    fn read(path: &Path, source: TokenSource) -> Result<Self, Error> {
+        check_permissions(path)?;
+        let contents = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Unable to read {}: {e}", path.display()))?;
+        Self::parse(contents.as_str(), source)
+    }
+
+    pub(crate) fn parse(raw: &str, source: TokenSource) -> Result<Self, Error> {
+        let token = raw.trim();
+        let token = token.strip_prefix("session=").unwrap_or(token);
+
+        if token.is_empty() {
+            return Err(anyhow!("Session token from {source} is empty"));
+        }
+        if !token.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(anyhow!("Session token from {source} is not a hex string"));
+        }
+
+        Ok(Self { token: token.to_string(), source })
+    }
+
+    pub fn source(&self) -> TokenSource {
+        self.source
+    }
+
+    pub fn cookie(&self) -> String {
+        format!("session={}", self.token)
+    }
+
+}
+
+impl Debug for SessionToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionToken")
+            .field("token", &"<redacted>")
+            .field("source", &self.source)
+            .finish()
+    }
+}
+
+impl std::fmt::Display for TokenSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenSource::Env => write!(f, "${SESSION_ENV}"),
+            TokenSource::Project => write!(f, "{SESSION_FILE}"),
+            TokenSource::Config => write!(f, "config dir"),
+        }
+    }
+}
+
+pub fn config_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .map(|dir| dir.join(CONFIG_DIR))
+}
+
+fn config_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(CONFIG_FILE))
+}
+
+#[cfg(unix)]
+pub fn check_permissions(path: &Path) -> Result<(), Error> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = fs::metadata(path)?.permissions().mode();
+    if mode & 0o004 != 0 {
+        return Err(anyhow!("Refusing to read {}: file is world-readable (mode {:o}), run `chmod 600` on it",
+            path.display(), mode & 0o777));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn check_permissions(_path: &Path) -> Result<(), Error> {
+    Ok(())
+}
+
+pub fn whoami(client: &AocClient) -> Result<String, Error> {
+    let source = client.session().source();
+    let response = client.get(format!("/{YEAR}/settings").as_str(), CachePolicy::Never)
+        .map_err(|e| anyhow!("Session token from {source} was rejected: {e}"))?;
+
+    parse_user(response.body.as_str())
+        .ok_or_else(|| anyhow!("Session token from {source} is expired or invalid"))
+}
+
+fn parse_user(html: &str) -> Option<String> {
+    let regex = Regex::new(r#"<div class="user">([^<]*)"#).unwrap();
+    regex.captures(html)
+        .map(|c| c[1].trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let token = SessionToken::parse("session=53616c7465645f5f\n", TokenSource::Project).expect("Parse");
+        assert_eq!(token.cookie(), "session=53616c7465645f5f");
+        assert!(SessionToken::parse("  ", TokenSource::Env).is_err());
+        assert!(SessionToken::parse("not a token", TokenSource::Env).is_err());
+    }
+
+    #[test]
+    fn redacted() {
+        let token = SessionToken::parse("53616c7465645f5f", TokenSource::Env).expect("Parse");
+        assert!(!format!("{token:?}").contains("53616c7465645f5f"));
+
+        let err = SessionToken::parse("53616c74zz", TokenSource::Env).unwrap_err();
+        assert!(!format!("{err:?}").contains("53616c74"));
+    }
+
+    #[test]
+    fn user() {
+        let html = r#"<div><div class="user">Ryan Tenney <span class="star-count">50*</span></div></div>"#;
+        assert_eq!(parse_user(html), Some("Ryan Tenney".to_string()));
+        assert_eq!(parse_user(r#"<a href="/2024/auth/login">[Log In]</a>"#), None);
+    }
+
+}
@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::hash::Hash;
+use crate::grid::{GridLike, Point};
+
+// What a SparseGrid can be keyed by. lower and upper combine two points axis
+// by axis, on_edge says whether a point lies on a face of the (min, max) box.
+pub trait Coord: Copy + Eq + Hash {
+    fn lower(self, other: Self) -> Self;
+    fn upper(self, other: Self) -> Self;
+    fn on_edge(self, min: Self, max: Self) -> bool;
+}
+
+impl Coord for Point {
+    fn lower(self, other: Self) -> Self {
+        Point::new(self.x.min(other.x), self.y.min(other.y))
+    }
+
+    fn upper(self, other: Self) -> Self {
+        Point::new(self.x.max(other.x), self.y.max(other.y))
+    }
+
+    fn on_edge(self, min: Self, max: Self) -> bool {
+        self.x == min.x || self.x == max.x || self.y == min.y || self.y == max.y
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SparseGrid<T, P = Point> {
+    cells: HashMap<P, T>,
+    bounds: Option<(P, P)>,
+}
+
+impl<T> SparseGrid<T> {
+
+    // Cells for which the closure returns None are left empty, so '.' floors
+    // and the like take no space.
+    pub fn parse_with<'a>(input: impl IntoIterator<Item = &'a str>, mut cell: impl FnMut(char) -> Option<T>) -> SparseGrid<T> {
+        let mut grid = Self::new();
+        for (y, line) in input.into_iter().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                if let Some(value) = cell(c) {
+                    grid.insert(Point::new(x as isize, y as isize), value);
+                }
+            }
+        }
+        grid
+    }
+
+}
+
+impl<T, P: Coord> SparseGrid<T, P> {
+
+    pub fn new() -> SparseGrid<T, P> {
+        SparseGrid { cells: HashMap::new(), bounds: None }
+    }
+
+    pub fn bounds(&self) -> Option<(P, P)> {
+        self.bounds
+    }
+
+    pub fn insert(&mut self, point: P, value: T) -> Option<T> {
+        self.bounds = bounds_of(self.bounds.into_iter().flat_map(|(min, max)| [min, max]).chain([point]));
+        self.cells.insert(point, value)
+    }
+
+    pub fn get(&self, point: P) -> Option<&T> {
+        self.cells.get(&point)
+    }
+
+    pub fn get_mut(&mut self, point: P) -> Option<&mut T> {
+        self.cells.get_mut(&point)
+    }
+
+    // Removing a cell on the edge of the bounds may shrink them, so they are
+    // recomputed from what is left; interior removals leave them alone.
+    pub fn remove(&mut self, point: P) -> Option<T> {
+        let value = self.cells.remove(&point)?;
+        if self.bounds.is_some_and(|(min, max)| point.on_edge(min, max)) {
+            self.bounds = bounds_of(self.cells.keys().copied());
+        }
+        Some(value)
+    }
+
+    pub fn contains(&self, point: P) -> bool {
+        self.cells.contains_key(&point)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&P, &T)> {
+        self.cells.iter()
+    }
+
+}
+
+fn bounds_of<P: Coord>(points: impl Iterator<Item = P>) -> Option<(P, P)> {
+    points.fold(None, |bounds, point| Some(match bounds {
+        Some((min, max)) => (min.lower(point), max.upper(point)),
+        None => (point, point),
+    }))
+}
+
+impl<T> GridLike<T> for SparseGrid<T> {
+    fn cell(&self, point: Point) -> Option<&T> {
+        self.get(point)
+    }
+
+    fn area(&self) -> Option<(Point, Point)> {
+        self.bounds
+    }
+
+    fn occupied(&self) -> Vec<Point> {
+        let mut points: Vec<Point> = self.cells.keys().copied().collect();
+        points.sort_by_key(|point| (point.y, point.x));
+        points
+    }
+}
+
+impl<T> Display for SparseGrid<T> where T: Display {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some((min, max)) = self.bounds {
+            for y in min.y..=max.y {
+                for x in min.x..=max.x {
+                    match self.get(Point::new(x, y)) {
+                        Some(value) => write!(f, "{}", value)?,
+                        None => write!(f, ".")?,
+                    }
+                }
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::grid::{Direction, Grid};
+
+    #[test]
+    fn grows() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(grid.bounds(), None);
+        grid.insert(Point::new(0, 0), '#');
+        grid.insert(Point::new(-2, 1), '#');
+        grid.insert(Point::new(1_000_000, -1), '#');
+        assert_eq!(grid.bounds(), Some((Point::new(-2, -1), Point::new(1_000_000, 1))));
+        assert_eq!(grid.len(), 3);
+
+        assert_eq!(grid.remove(Point::new(1_000_000, -1)), Some('#'));
+        assert_eq!(grid.bounds(), Some((Point::new(-2, 0), Point::new(0, 1))));
+        assert_eq!(grid.to_string(), "..#\n#..\n");
+        assert_eq!(grid.remove(Point::new(5, 5)), None);
+
+        grid.insert(Point::new(0, 1), 'O');
+        assert!(grid.contains(Point::new(0, 1)));
+        assert_eq!(grid.occupied(), vec![Point::new(0, 0), Point::new(-2, 1), Point::new(0, 1)]);
+
+        grid.remove(Point::new(0, 0));
+        grid.remove(Point::new(-2, 1));
+        grid.remove(Point::new(0, 1));
+        assert_eq!((grid.bounds(), grid.to_string().as_str()), (None, ""));
+    }
+
+    #[test]
+    fn shared_with_dense() {
+        let lines = ["S.#", "..#", "#.E"];
+        let dense: Grid<char> = Grid::parse(lines).expect("Parse");
+        let sparse = SparseGrid::parse_with(lines, |c| (c != '#').then_some(c));
+        assert_eq!(sparse.to_string(), "S..\n...\n..E\n");
+
+        assert_eq!(steps_to_end(&dense), Some(4));
+        assert_eq!(steps_to_end(&sparse), Some(4));
+        assert_eq!(sparse.neighbours(Point::new(1, 1), &Direction::ALL).len(), 3);
+        assert_eq!(sparse.render(|c| if c.is_some() { 'o' } else { '#' }), "oo#\noo#\n#oo\n");
+    }
+
+    fn steps_to_end<G: GridLike<char>>(grid: &G) -> Option<usize> {
+        let start = grid.find(|c| *c == 'S')?;
+        let end = grid.find(|c| *c == 'E')?;
+        grid.distances(start, |c| *c != '#').get(&end).copied()
+    }
+
+}
@@ -3,8 +3,9 @@ use std::time::Duration;
 use std::path::Path;
 use chrono::{Datelike, Utc};
 use chrono_tz::America::New_York;
-use reqwest::blocking::Client;
 use crate::aocday::{AocInfo, AocInput};
+use crate::client::{self, AocClient, CachePolicy};
+use crate::crypt::read_encrypted_input;
 use crate::YEAR;
 
 fn read_lines(name: String) -> Result<Vec<String>, std::io::Error> {
@@ -15,40 +16,45 @@ fn read_lines(name: String) -> Result<Vec<String>, std::io::Error> {
     Ok(contents.lines().map(str::to_string).collect())
 }
 
-fn read_session_token() -> Result<String, anyhow::Error> {
-    let mut path = env::current_dir()?;
-    path.push(".session_token");
+pub fn read_input(day: u8) -> Result<AocInput, anyhow::Error> {
+    let contents = match read_local_input(day)? {
+        Some(contents) => contents,
+        None => download_input(day)?,
+    };
 
-    let contents = fs::read_to_string(path)?;
-    Ok(contents.trim().to_string())
+    Ok(contents.into())
 }
 
-pub fn read_input(day: u8) -> Result<AocInput, anyhow::Error> {
-    let mut path = env::current_dir()?;
-    path.push(format!("src/day{day}.txt"));
-
-    let contents = if !path.exists() {
-        download_input(day, path)?
-    } else {
-        fs::read_to_string(path)?
-    };
+pub fn read_local_input(day: u8) -> Result<Option<String>, anyhow::Error> {
+    if let Some(contents) = read_encrypted_input(day)? {
+        return Ok(Some(contents));
+    }
 
-    Ok(AocInput::new(contents.as_str()))
+    read_plain_input(day)
 }
 
-fn download_input<P: AsRef<Path>>(day: u8, path: P) -> Result<String, anyhow::Error> {
-    let session_token = read_session_token()?;
-    let url = format!("https://adventofcode.com/2024/day/{}/input", day);
-    let input = Client::new().get(&url)
-        .header("Cookie", format!("session={session_token}"))
-        .header("User-Agent", "github.com/ryantenney/advent-of-code-2024-rust by ryan@10e.us")
-        .send()
-        .unwrap()
-        .text()?;
+// The cached download or the old src/dayN.txt, never the encrypted copy.
+pub fn read_plain_input(day: u8) -> Result<Option<String>, anyhow::Error> {
+    if let Some(cached) = client::cached(input_path(day).as_str()) {
+        return Ok(Some(cached.body));
+    }
+
+    let mut legacy_path = env::current_dir()?;
+    legacy_path.push(format!("src/day{day}.txt"));
+    if legacy_path.exists() {
+        return Ok(Some(fs::read_to_string(legacy_path)?));
+    }
 
-    fs::write(path, &input)?;
+    Ok(None)
+}
+
+fn download_input(day: u8) -> Result<String, anyhow::Error> {
+    let client = AocClient::new()?;
+    Ok(client.get(input_path(day).as_str(), CachePolicy::Forever)?.body)
+}
 
-    Ok(input)
+pub fn input_path(day: u8) -> String {
+    format!("/{YEAR}/day/{day}/input")
 }
 
 pub fn end_day(year: i32) -> Option<u8> {